@@ -0,0 +1,159 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use ibkr_flex_statement::Parser;
+use ibkr_flex_statement::statement_ref::FlexDocument;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The system allocator, counting every allocation so benchmarks can report them.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Runs `f`, returning its result and the number of allocations it made.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    (result, ALLOCATIONS.load(Ordering::Relaxed) - before)
+}
+
+const TRADE_COUNT: usize = 20_000;
+const POSITION_COUNT: usize = 20_000;
+
+fn large_statement(trade_count: usize) -> String {
+    large_mixed_statement(trade_count, 0)
+}
+
+/// Builds a statement with `trade_count` trades and `position_count` open positions.
+fn large_mixed_statement(trade_count: usize, position_count: usize) -> String {
+    let mut xml = String::from(
+        r##"<FlexQueryResponse queryName="example-query" type="AF">
+            <FlexStatements count="1">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
+                    <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks" />
+                    <Trades>
+"##,
+    );
+    for i in 0..trade_count {
+        xml.push_str(&format!(
+            r##"<Trade accountId="U1234567" currency="USD" symbol="ARGX" conid="276343981" listingExchange="NASDAQ" tradeID="{i}" reportDate="2025-04-25" dateTime="2025-04-25;10:19:55 EDT" tradeDate="2025-04-25" transactionType="ExchTrade" exchange="BYX" quantity="1" tradePrice="606.57" tradeMoney="606.57" proceeds="-606.57" ibCommission="-1.000035" ibCommissionCurrency="USD" netCash="-607.570035" closePrice="614.76" openCloseIndicator="O" cost="607.570035" fifoPnlRealized="0" mtmPnl="8.19" buySell="BUY" ibOrderID="4015030800" transactionID="{i}" ibExecID="0000edae.680b59d1.01.01" orderTime="2025-04-25;10:19:55 EDT" orderType="LMT" accruedInt="0" assetCategory="STK" brokerageOrderID="002ce642.00014b44.680b0ed6.0001" orderReference="" isAPIOrder="N" />
+"##
+        ));
+    }
+    xml.push_str("                    </Trades>\n                    <OpenPositions>\n");
+    for i in 0..position_count {
+        xml.push_str(&format!(
+            r##"<OpenPosition accountId="U1234567" currency="USD" assetCategory="STK" symbol="S{i}" conid="{i}" listingExchange="NASDAQ" reportDate="2025-04-25" position="500" markPrice="225.38" positionValue="112690" openPrice="217.200032892" costBasisPrice="217.200032892" percentOfNAV="3.08" fifoPnlUnrealized="4089.983554" side="Long" openDateTime="" holdingPeriodDateTime="" accruedInt="" commodityType="" />
+"##
+        ));
+    }
+    xml.push_str(
+        r##"                    </OpenPositions>
+                </FlexStatement>
+            </FlexStatements>
+        </FlexQueryResponse>"##,
+    );
+    xml
+}
+
+fn parse_large_statement(c: &mut Criterion) {
+    let parser = Parser::new();
+    let xml = large_statement(TRADE_COUNT);
+
+    c.bench_function("parse_large_statement", |b| {
+        b.iter(|| parser.parse_flex_query_response(black_box(&xml)).unwrap())
+    });
+}
+
+// Collects the execution ids of the same trades, once by converting each `TradeRef` into an
+// owned `Trade` and once through the borrowed `TradeRef`s, which allocate no `String` per
+// attribute. The allocations of one pass of each are printed before timing them.
+fn collect_execution_ids(c: &mut Criterion) {
+    let parser = Parser::new();
+    let xml = large_statement(TRADE_COUNT);
+    let document = FlexDocument::parse(&xml).unwrap();
+    let statements = document.statements().unwrap();
+    let trades = statements[0].trades().collect::<Vec<_>>();
+
+    let owned = || {
+        black_box(&trades)
+            .iter()
+            .map(|t| t.to_trade(&parser).unwrap().execution_id.len())
+            .sum::<usize>()
+    };
+    let borrowed = || {
+        black_box(&trades)
+            .iter()
+            .filter_map(|t| t.execution_id())
+            .map(str::len)
+            .sum::<usize>()
+    };
+
+    let (owned_len, owned_allocations) = count_allocations(owned);
+    let (borrowed_len, borrowed_allocations) = count_allocations(borrowed);
+    assert_eq!(owned_len, borrowed_len);
+    assert_eq!(borrowed_allocations, 0);
+    println!(
+        "collect_execution_ids over {} trades: {} allocations owned, {} borrowed",
+        trades.len(),
+        owned_allocations,
+        borrowed_allocations
+    );
+
+    c.bench_function("collect_execution_ids_owned", |b| b.iter(owned));
+    c.bench_function("collect_execution_ids_borrowed", |b| b.iter(borrowed));
+}
+
+// Section vectors are pre-sized from their wrapper elements; compare runs across changes
+// with `cargo bench -- --save-baseline before` and `cargo bench -- --baseline before`.
+fn parse_large_mixed_statement(c: &mut Criterion) {
+    let parser = Parser::new();
+    let xml = large_mixed_statement(TRADE_COUNT, POSITION_COUNT);
+
+    c.bench_function("parse_large_mixed_statement", |b| {
+        b.iter(|| parser.parse_flex_query_response(black_box(&xml)).unwrap())
+    });
+}
+
+// Parses the same response with the DOM backend and, when built with `--features fast`,
+// the pull parser.
+fn parse_backends(c: &mut Criterion) {
+    let parser = Parser::new();
+    let xml = large_mixed_statement(TRADE_COUNT, POSITION_COUNT);
+
+    c.bench_function("parse_backend_dom", |b| {
+        b.iter(|| {
+            parser
+                .parse_flex_query_response_dom(black_box(&xml))
+                .unwrap()
+        })
+    });
+
+    #[cfg(feature = "fast")]
+    c.bench_function("parse_backend_fast", |b| {
+        b.iter(|| parser.parse_flex_query_response(black_box(&xml)).unwrap())
+    });
+}
+
+criterion_group!(
+    benches,
+    parse_large_statement,
+    parse_large_mixed_statement,
+    parse_backends,
+    collect_execution_ids
+);
+criterion_main!(benches);
@@ -1,42 +1,64 @@
-use crate::node_utils::NodeWrapper;
-use crate::statement_section::StatementSection;
+use crate::currency::Currency;
+use crate::money::Money;
+use crate::node_utils::AttributeSource;
+use crate::statement_section::{StatementSection, StatementSectionWithTimezone};
+use crate::time_utils;
 use anyhow::Result;
-
-#[derive(Debug, PartialEq)]
-pub enum PositionSide {
-    Long,
-    Short,
-}
+use chrono_tz::Tz;
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccountInfo {
     pub account_id: String,
 }
 
-impl<'a> TryFrom<&'a str> for PositionSide {
-    type Error = anyhow::Error;
-
-    fn try_from(s: &'a str) -> Result<Self> {
-        match s {
-            "Long" => Ok(Self::Long),
-            "Short" => Ok(Self::Short),
-            _ => Err(anyhow::Error::msg(format!("unknown position side {}", s))),
-        }
-    }
+/// The account's base currency and its rate, from the `AccountSummary` row.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BaseCurrency {
+    pub currency: Currency,
+    pub rate: Option<Money>,
+    pub rate_timestamp_ms: Option<i64>,
+    /// Who set the rate, e.g. `IBKR`.
+    pub rate_source: Option<String>,
+    /// When the rate source set the rate.
+    pub rate_source_timestamp_ms: Option<i64>,
 }
 
 impl StatementSection for AccountInfo {
-    fn from_node(node: &NodeWrapper) -> Result<AccountInfo> {
+    fn from_node(node: &impl AttributeSource) -> Result<AccountInfo> {
         Ok(AccountInfo {
             account_id: node.get_attribute("accountId")?,
         })
     }
 }
 
+impl StatementSectionWithTimezone for BaseCurrency {
+    fn from_node(
+        node: &impl AttributeSource,
+        tz_map: &HashMap<String, Tz>,
+    ) -> Result<BaseCurrency> {
+        let timestamp_ms = |attribute_name: &str| {
+            node.get_attribute_opt(attribute_name)
+                .map(|s| time_utils::parse_ibkr_datetime_ms(tz_map, &s))
+                .transpose()
+        };
+        Ok(BaseCurrency {
+            currency: Currency::try_from(node.get_attribute("accountBaseCurrency")?.as_str())?,
+            rate: node.parse_attribute_opt("accountBaseCurrencyRate")?,
+            rate_timestamp_ms: timestamp_ms("accountBaseCurrencyRateDateTime")?,
+            rate_source: node.get_attribute_opt("accountBaseCurrencyRateSource"),
+            rate_source_timestamp_ms: timestamp_ms("accountBaseCurrencyRateSourceDateTime")?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Parser;
+    use crate::money::money;
     use anyhow::Result;
 
     const PARTIAL_STATEMENT_EXAMPLE: &str = r##"
@@ -57,7 +79,7 @@ mod tests {
 
     #[test]
     fn account_info_parses() -> Result<()> {
-        let statements = Parser::new()?.parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
         assert_eq!(statements.len(), 1);
         let result = &statements[0];
 
@@ -69,4 +91,29 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn base_currency_parses_rate_source() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        assert_eq!(
+            statements[0].base_currency,
+            Some(BaseCurrency {
+                currency: Currency::USD,
+                rate: Some(money("1.0")),
+                // 2025-04-26 13:34:28 EDT
+                rate_timestamp_ms: Some(1745688868000),
+                rate_source: Some("IBKR".to_string()),
+                rate_source_timestamp_ms: Some(1745688868000),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn base_currency_is_optional() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen("<AccountSummary ", "<Ignored ", 1);
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        assert_eq!(statements[0].base_currency, None);
+        Ok(())
+    }
 }
@@ -0,0 +1,132 @@
+use crate::currency::Currency;
+use crate::money::{self, Money};
+use crate::trade::{Trade, TradeSide};
+use std::collections::HashMap;
+
+/// Execution-level trades sharing an `ibOrderID`, rolled up into a single order.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AggregatedOrder {
+    pub ib_order_id: String,
+    pub conid: u64,
+    pub currency: Currency,
+    pub ticker: String,
+    /// The side shared by every execution, or `None` if the order id mixes buys and sells.
+    pub side: Option<TradeSide>,
+    pub execution_count: usize,
+    /// Total filled quantity, summed over the absolute quantity of each execution.
+    pub quantity: f64,
+    /// Volume-weighted average fill price.
+    pub average_price: Money,
+    pub commission: Money,
+    pub first_execution_timestamp_ms: i64,
+    pub last_execution_timestamp_ms: i64,
+}
+
+/// Groups trades by `ib_order_id`, keeping orders in the order they first appear.
+pub(crate) fn aggregate_orders<'a>(
+    trades: impl IntoIterator<Item = &'a Trade>,
+) -> Vec<AggregatedOrder> {
+    let mut orders: Vec<AggregatedOrder> = Vec::new();
+    let mut notionals: Vec<Money> = Vec::new();
+    let mut index_by_order_id: HashMap<&str, usize> = HashMap::new();
+
+    for trade in trades {
+        let quantity = trade.quantity.abs();
+        let notional = trade.price * money::from_f64(quantity);
+
+        match index_by_order_id.get(trade.ib_order_id.as_str()) {
+            Some(&i) => {
+                let order = &mut orders[i];
+                if order.side != Some(trade.side) {
+                    order.side = None;
+                }
+                order.execution_count += 1;
+                order.quantity += quantity;
+                order.commission += trade.commission;
+                order.first_execution_timestamp_ms = order
+                    .first_execution_timestamp_ms
+                    .min(trade.execution_timestamp_ms);
+                order.last_execution_timestamp_ms = order
+                    .last_execution_timestamp_ms
+                    .max(trade.execution_timestamp_ms);
+                notionals[i] += notional;
+            }
+            None => {
+                index_by_order_id.insert(&trade.ib_order_id, orders.len());
+                orders.push(AggregatedOrder {
+                    ib_order_id: trade.ib_order_id.clone(),
+                    conid: trade.conid,
+                    currency: trade.currency.clone(),
+                    ticker: trade.ticker.clone(),
+                    side: Some(trade.side),
+                    execution_count: 1,
+                    quantity,
+                    average_price: Money::default(),
+                    commission: trade.commission,
+                    first_execution_timestamp_ms: trade.execution_timestamp_ms,
+                    last_execution_timestamp_ms: trade.execution_timestamp_ms,
+                });
+                notionals.push(notional);
+            }
+        }
+    }
+
+    for (order, notional) in orders.iter_mut().zip(notionals) {
+        if order.quantity != 0.0 {
+            order.average_price = notional / money::from_f64(order.quantity);
+        }
+    }
+    orders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::money::money;
+    use anyhow::Result;
+
+    const PARTIAL_STATEMENT_EXAMPLE: &str = r##"
+        <FlexQueryResponse queryName="example-query" type="AF">
+            <FlexStatements count="1">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
+                    <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks,Options,Warrants,Forex,Futures,Crypto Currencies,Mutual Funds,Fully Paid Stock Loan" />
+                    <Trades>
+                        <Trade accountId="U1234567" currency="USD" symbol="GEO" conid="158655765" listingExchange="NYSE" tradeID="7587946875" reportDate="2025-04-25" dateTime="2025-04-25;11:24:28 EDT" tradeDate="2025-04-25" transactionType="ExchTrade" exchange="NYSE" quantity="100" tradePrice="10" tradeMoney="1000" proceeds="-1000" ibCommission="-1" ibCommissionCurrency="USD" netCash="-1001" closePrice="10.5" openCloseIndicator="O" cost="1001" fifoPnlRealized="0" mtmPnl="50" buySell="BUY" ibOrderID="4015577648" transactionID="32582764875" ibExecID="00012e0e.680b7717.01.01" orderTime="2025-04-25;11:24:26 EDT" orderType="LMT" accruedInt="0" assetCategory="STK" brokerageOrderID="002ce642.00014b44.680b0fbf.0001" orderReference="" isAPIOrder="N" />
+                        <Trade accountId="U1234567" currency="USD" symbol="ARGX" conid="276343981" listingExchange="NASDAQ" tradeID="7587063231" reportDate="2025-04-25" dateTime="2025-04-25;11:30:00 EDT" tradeDate="2025-04-25" transactionType="ExchTrade" exchange="BYX" quantity="1" tradePrice="606.57" tradeMoney="606.57" proceeds="-606.57" ibCommission="-1.000035" ibCommissionCurrency="USD" netCash="-607.570035" closePrice="614.76" openCloseIndicator="O" cost="607.570035" fifoPnlRealized="0" mtmPnl="8.19" buySell="BUY" ibOrderID="4015030800" transactionID="32580112485" ibExecID="0000edae.680b59d1.01.01" orderTime="2025-04-25;11:29:59 EDT" orderType="LMT" accruedInt="0" assetCategory="STK" brokerageOrderID="002ce642.00014b44.680b0ed6.0001" orderReference="" isAPIOrder="N" />
+                        <Trade accountId="U1234567" currency="USD" symbol="GEO" conid="158655765" listingExchange="NYSE" tradeID="7587946880" reportDate="2025-04-25" dateTime="2025-04-25;11:25:28 EDT" tradeDate="2025-04-25" transactionType="ExchTrade" exchange="ARCA" quantity="300" tradePrice="11" tradeMoney="3300" proceeds="-3300" ibCommission="-1.5" ibCommissionCurrency="USD" netCash="-3301.5" closePrice="10.5" openCloseIndicator="O" cost="3301.5" fifoPnlRealized="0" mtmPnl="-150" buySell="BUY" ibOrderID="4015577648" transactionID="32582764880" ibExecID="00012e0e.680b7717.01.02" orderTime="2025-04-25;11:24:26 EDT" orderType="LMT" accruedInt="0" assetCategory="STK" brokerageOrderID="002ce642.00014b44.680b0fbf.0001" orderReference="" isAPIOrder="N" />
+                    </Trades>
+                </FlexStatement>
+            </FlexStatements>
+         </FlexQueryResponse>
+        "##;
+
+    #[test]
+    fn executions_aggregate_into_orders() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let orders = result.aggregate_orders();
+        assert_eq!(orders.len(), 2);
+
+        let geo = &orders[0];
+        assert_eq!(geo.ib_order_id, "4015577648");
+        assert_eq!(geo.ticker, "GEO");
+        assert_eq!(geo.side, Some(TradeSide::Buy));
+        assert_eq!(geo.execution_count, 2);
+        assert_eq!(geo.quantity, 400.0);
+        // (100 * 10 + 300 * 11) / 400
+        assert!((geo.average_price - money("10.75")).abs() < money("0.000001"));
+        assert!((geo.commission - money("-2.5")).abs() < money("0.000001"));
+        assert_eq!(
+            geo.last_execution_timestamp_ms - geo.first_execution_timestamp_ms,
+            60_000
+        );
+
+        let argx = &orders[1];
+        assert_eq!(argx.execution_count, 1);
+        assert_eq!(argx.average_price, money("606.57"));
+        Ok(())
+    }
+}
@@ -0,0 +1,344 @@
+//! Tolerant comparison of parsed rows.
+//!
+//! The derived `PartialEq` compares amounts exactly, so rows whose values were recomputed or
+//! round-tripped through another format rarely compare equal. [`ApproxEq`] compares
+//! [`Money`] and `f64` fields within an absolute tolerance and every other field exactly.
+//!
+//! # Examples
+//!
+//! ```
+//! use ibkr_flex_statement::approx_eq::ApproxEq;
+//!
+//! assert!(0.1_f64.approx_eq(&(0.3 - 0.2), 1e-9));
+//! assert!(!Some(1.0_f64).approx_eq(&None, 1e-9));
+//! ```
+
+use crate::account_info::AccountInfo;
+use crate::asset_category::AssetCategory;
+use crate::cash_report::CashReport;
+use crate::conversion_rate::ConversionRate;
+use crate::currency::Currency;
+use crate::equity_summary::EquitySummary;
+use crate::fifo_performance_summary::FIFOPerformanceSummary;
+#[cfg(feature = "decimal")]
+use crate::money::{self, Money};
+use crate::net_stock_position::NetStockPosition;
+use crate::open_position::{OpenPosition, PositionSide};
+use crate::stmt_funds::FundsLine;
+use crate::trade::{
+    LevelOfDetail, OpenCloseIndicator, OrderType, Trade, TradeCode, TradeSide, TransactionType,
+};
+use crate::trade_confirm::TradeConfirm;
+use chrono::NaiveDate;
+
+/// Equality within an absolute tolerance, `epsilon`, for floating point fields.
+pub trait ApproxEq {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool;
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self - other).abs() <= epsilon
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl ApproxEq for Money {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self - other).abs() <= money::from_f64(epsilon)
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Option<T> {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.approx_eq(b, epsilon),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Vec<T> {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.approx_eq(b, epsilon))
+    }
+}
+
+/// Implements [`ApproxEq`] as exact equality for types without floating point values.
+macro_rules! impl_exact {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ApproxEq for $ty {
+                fn approx_eq(&self, other: &Self, _epsilon: f64) -> bool {
+                    self == other
+                }
+            }
+        )*
+    };
+}
+
+impl_exact!(
+    bool,
+    i64,
+    u64,
+    String,
+    AssetCategory,
+    Currency,
+    LevelOfDetail,
+    OpenCloseIndicator,
+    OrderType,
+    PositionSide,
+    TradeCode,
+    TradeSide,
+    TransactionType,
+    NaiveDate,
+);
+
+/// Implements [`ApproxEq`] for a struct field by field. The struct is destructured
+/// exhaustively, so adding a field without listing it here fails to compile.
+macro_rules! impl_fields {
+    ($ty:ident { $($field:ident),* $(,)? }) => {
+        impl ApproxEq for $ty {
+            fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+                let $ty { $($field),* } = self;
+                $($field.approx_eq(&other.$field, epsilon))&&*
+            }
+        }
+    };
+}
+
+impl_fields!(AccountInfo { account_id });
+
+impl_fields!(CashReport {
+    account_id,
+    currency,
+    start_timestamp_ms,
+    end_timestamp_ms,
+    starting_cash,
+    ending_cash,
+    ending_settled_cash,
+    net_trade_purchases,
+    net_trade_sales,
+    commissions,
+    commissions_mtd,
+    commissions_ytd,
+    other_fees,
+    other_fees_mtd,
+    other_fees_ytd,
+    broker_fees,
+    broker_fees_mtd,
+    broker_fees_ytd,
+    dividends,
+    dividends_mtd,
+    dividends_ytd,
+    interest,
+    interest_mtd,
+    interest_ytd,
+    deposits,
+    deposits_mtd,
+    deposits_ytd,
+    withdrawals,
+    withdrawals_mtd,
+    withdrawals_ytd,
+});
+
+impl_fields!(ConversionRate {
+    from_currency,
+    rate,
+    report_date,
+    timestamp_eod_ms,
+    to_currency,
+});
+
+impl_fields!(EquitySummary {
+    account_id,
+    cash_balance,
+    cash_balance_long,
+    cash_balance_short,
+    commodities,
+    commodities_long,
+    commodities_short,
+    currency,
+    dividend_accruals,
+    funds,
+    funds_long,
+    funds_short,
+    interest_accrual_mtd,
+    interest_accrual_mtd_long,
+    interest_accrual_mtd_short,
+    report_date,
+    stock_balance,
+    stock_balance_long,
+    stock_balance_short,
+    timestamp_eod_ms,
+    total,
+    total_long,
+    total_short,
+});
+
+impl_fields!(FIFOPerformanceSummary {
+    account_id,
+    report_date,
+    timestamp_eod_ms,
+    asset_category,
+    ticker,
+    conid,
+    listing_exchange,
+    currency,
+    realized_st_profit,
+    realized_st_loss,
+    unrealized_st_profit,
+    unrealized_st_loss,
+    realized_lt_profit,
+    realized_lt_loss,
+    unrealized_lt_profit,
+    unrealized_lt_loss,
+    total_realized_pnl,
+    total_fifo_pnl,
+});
+
+impl_fields!(FundsLine {
+    account_id,
+    activity_code,
+    activity_description,
+    amount,
+    balance,
+    conid,
+    currency,
+    ticker,
+    timestamp_ms,
+});
+
+impl_fields!(NetStockPosition {
+    account_id,
+    asset_category,
+    conid,
+    currency,
+    listing_exchange,
+    net_shares,
+    ticker,
+});
+
+impl_fields!(OpenPosition {
+    account_id,
+    accrued_interest,
+    asset_category,
+    conid,
+    cost_basis_price,
+    fifo_pnl_unrealized,
+    currency,
+    holding_period_timestamp_ms,
+    listing_exchange,
+    mark_price,
+    open_timestamp_ms,
+    open_quantity,
+    percent_of_nav,
+    position_value,
+    report_date,
+    timestamp_eod_ms,
+    ticker,
+    side,
+});
+
+impl_fields!(Trade {
+    account_id,
+    close_price,
+    codes,
+    conid,
+    currency,
+    execution_exchange,
+    execution_id,
+    execution_timestamp_ms,
+    commission,
+    commission_currency,
+    fifo_pnl_realized,
+    ib_order_id,
+    is_api_order,
+    is_cancel,
+    level_of_detail,
+    listing_exchange,
+    open_close_indicator,
+    order_id,
+    order_reference,
+    order_timestamp_ms,
+    order_type,
+    price,
+    proceeds,
+    quantity,
+    side,
+    ticker,
+    trade_date,
+    trade_id,
+    trade_money,
+    transaction_id,
+    transaction_type,
+});
+
+impl_fields!(TradeConfirm {
+    account_id,
+    asset_category,
+    codes,
+    commission,
+    commission_currency,
+    conid,
+    currency,
+    execution_exchange,
+    execution_id,
+    execution_timestamp_ms,
+    ib_order_id,
+    is_api_order,
+    is_cancel,
+    listing_exchange,
+    net_cash,
+    order_id,
+    order_reference,
+    order_type,
+    price,
+    proceeds,
+    quantity,
+    settle_date,
+    side,
+    ticker,
+    trade_date,
+    trade_id,
+    trade_money,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::money::money;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+    use anyhow::Result;
+
+    #[test]
+    fn equity_summaries_compare_within_tolerance() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let summary = &statements[0].equity_summaries[0];
+        let mut recomputed = summary.clone();
+        recomputed.total += money("0.000000001");
+
+        assert_ne!(summary, &recomputed);
+        assert!(summary.approx_eq(&recomputed, 1e-6));
+        assert!(!summary.approx_eq(&recomputed, 1e-12));
+
+        recomputed.report_date = "2025-04-25".to_string();
+        assert!(!summary.approx_eq(&recomputed, 1e-6));
+        Ok(())
+    }
+
+    #[test]
+    fn trades_compare_within_tolerance() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let trade = &statements[0].trades[0];
+        let mut recomputed = trade.clone();
+        recomputed.commission += money("0.000000001");
+        recomputed.quantity += 1e-9;
+
+        assert!(trade.approx_eq(&recomputed, 1e-6));
+        assert!(!trade.approx_eq(&statements[0].trades[1], 1e-6));
+        Ok(())
+    }
+}
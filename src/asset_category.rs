@@ -1,6 +1,9 @@
 use anyhow::Result;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AssetCategory {
     Crypto,
     Stock,
@@ -9,7 +12,7 @@ pub enum AssetCategory {
     // Option,
     // Future,
     // Forex,
-    // Crypto,
+    Other(String),
 }
 
 impl<'a> TryFrom<&'a str> for AssetCategory {
@@ -19,10 +22,47 @@ impl<'a> TryFrom<&'a str> for AssetCategory {
         match s {
             "CRYPTO" => Ok(Self::Crypto),
             "STK" => Ok(Self::Stock),
-            _ => Err(anyhow::Error::msg(format!(
-                "unsupported asset category {}",
-                s
-            ))),
+            "" => Err(anyhow::Error::msg("empty asset category")),
+            _ => Ok(Self::Other(s.to_string())),
         }
     }
 }
+
+impl FromStr for AssetCategory {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        AssetCategory::try_from(s)
+    }
+}
+
+impl Display for AssetCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Crypto => f.write_str("CRYPTO"),
+            Self::Stock => f.write_str("STK"),
+            Self::Other(s) => f.write_str(s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn asset_category_display_round_trips() -> Result<()> {
+        assert_eq!("STK".parse::<AssetCategory>()?, AssetCategory::Stock);
+        assert_eq!(AssetCategory::Stock.to_string(), "STK");
+
+        for code in ["CRYPTO", "STK", "OPT"] {
+            assert_eq!(code.parse::<AssetCategory>()?.to_string(), code);
+        }
+        assert_eq!(
+            "OPT".parse::<AssetCategory>()?,
+            AssetCategory::Other("OPT".to_string())
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+//! Rendering of trades as Beancount transactions.
+
+use crate::trade::{Trade, TradeSide};
+use std::fmt::Write;
+
+/// Account names used for the postings of each rendered transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BeancountOptions {
+    /// Prefix of the per-instrument asset accounts, e.g. `Assets:IBKR:ARGX`.
+    pub asset_prefix: String,
+    pub cash_account: String,
+    pub commission_account: String,
+    /// Balancing account for the gain or loss realized by sells.
+    pub pnl_account: String,
+}
+
+impl Default for BeancountOptions {
+    fn default() -> Self {
+        BeancountOptions {
+            asset_prefix: "Assets:IBKR".to_string(),
+            cash_account: "Assets:IBKR:Cash".to_string(),
+            commission_account: "Expenses:IBKR:Commissions".to_string(),
+            pnl_account: "Income:IBKR:PnL".to_string(),
+        }
+    }
+}
+
+/// Beancount commodities cannot contain spaces, which IBKR uses in symbols like `BRK B`.
+fn commodity(ticker: &str) -> String {
+    ticker.replace(' ', ".")
+}
+
+pub(crate) fn render_trades<'a>(
+    trades: impl IntoIterator<Item = &'a Trade>,
+    options: &BeancountOptions,
+) -> String {
+    let mut out = String::new();
+    for trade in trades {
+        write_transaction(&mut out, trade, options).expect("writing to a String cannot fail");
+    }
+    out
+}
+
+fn write_transaction(
+    out: &mut String,
+    trade: &Trade,
+    options: &BeancountOptions,
+) -> std::fmt::Result {
+    let date = trade.trade_date.format("%Y-%m-%d");
+    let commodity = commodity(&trade.ticker);
+    let currency = &trade.currency;
+    let side = match trade.side {
+        TradeSide::Buy => "BUY",
+        TradeSide::Sell => "SELL",
+    };
+
+    writeln!(
+        out,
+        "{} * \"{} {} {} @ {} {}\"",
+        date,
+        side,
+        trade.quantity.abs(),
+        commodity,
+        trade.price,
+        currency
+    )?;
+    let lot = match trade.side {
+        TradeSide::Buy => format!("{{{} {}}}", trade.price, currency),
+        TradeSide::Sell => format!("{{}} @ {} {}", trade.price, currency),
+    };
+    writeln!(
+        out,
+        "  {}:{}  {} {} {}",
+        options.asset_prefix, commodity, trade.quantity, commodity, lot
+    )?;
+    writeln!(
+        out,
+        "  {}  {} {}",
+        options.cash_account, -trade.trade_money, currency
+    )?;
+    writeln!(
+        out,
+        "  {}  {} {}",
+        options.cash_account, trade.commission, trade.commission_currency
+    )?;
+    writeln!(
+        out,
+        "  {}  {} {}",
+        options.commission_account, -trade.commission, trade.commission_currency
+    )?;
+    if trade.side == TradeSide::Sell {
+        writeln!(out, "  {}", options.pnl_account)?;
+    }
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+    use anyhow::Result;
+
+    #[test]
+    fn trades_render_as_transactions() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let rendered = statements[0].to_beancount(&BeancountOptions::default());
+
+        assert_eq!(
+            rendered,
+            r#"2025-04-25 * "BUY 1 ARGX @ 606.57 USD"
+  Assets:IBKR:ARGX  1 ARGX {606.57 USD}
+  Assets:IBKR:Cash  -606.57 USD
+  Assets:IBKR:Cash  -1.000035 USD
+  Expenses:IBKR:Commissions  1.000035 USD
+
+2025-04-25 * "BUY 1000 GEO @ 30.85 USD"
+  Assets:IBKR:GEO  1000 GEO {30.85 USD}
+  Assets:IBKR:Cash  -30850 USD
+  Assets:IBKR:Cash  -5.035 USD
+  Expenses:IBKR:Commissions  5.035 USD
+
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sells_post_to_pnl_account() -> Result<()> {
+        let mut statement = Parser::new()
+            .parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?
+            .remove(0);
+        let trade = &mut statement.trades[1];
+        trade.side = TradeSide::Sell;
+        trade.quantity = -trade.quantity;
+        trade.trade_money = -trade.trade_money;
+
+        let options = BeancountOptions {
+            asset_prefix: "Assets:Broker".to_string(),
+            cash_account: "Assets:Broker:USD".to_string(),
+            commission_account: "Expenses:Fees".to_string(),
+            pnl_account: "Income:Gains".to_string(),
+        };
+        let rendered = render_trades(&statement.trades[1..], &options);
+
+        assert_eq!(
+            rendered,
+            r#"2025-04-25 * "SELL 1000 GEO @ 30.85 USD"
+  Assets:Broker:GEO  -1000 GEO {} @ 30.85 USD
+  Assets:Broker:USD  30850 USD
+  Assets:Broker:USD  -5.035 USD
+  Expenses:Fees  5.035 USD
+  Income:Gains
+
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn commission_posts_in_commission_currency() -> Result<()> {
+        let xml = FULL_STATEMENT_EXAMPLE.replacen(
+            r#"ibCommission="-1.000035" ibCommissionCurrency="USD""#,
+            r#"ibCommission="-1.35" ibCommissionCurrency="CAD""#,
+            1,
+        );
+        let statement = Parser::new().parse_flex_query_response(&xml)?.remove(0);
+        let rendered = render_trades(&statement.trades[..1], &BeancountOptions::default());
+
+        assert_eq!(
+            rendered,
+            r#"2025-04-25 * "BUY 1 ARGX @ 606.57 USD"
+  Assets:IBKR:ARGX  1 ARGX {606.57 USD}
+  Assets:IBKR:Cash  -606.57 USD
+  Assets:IBKR:Cash  -1.35 CAD
+  Expenses:IBKR:Commissions  1.35 CAD
+
+"#
+        );
+        Ok(())
+    }
+}
@@ -1,58 +1,72 @@
 use crate::currency::Currency;
-use crate::node_utils::NodeWrapper;
-use crate::statement_section::StatementSection;
-use crate::time_utils;
+use crate::money::Money;
+use crate::node_utils::AttributeSource;
+use crate::statement_section::StatementSectionWithEod;
+use crate::time_utils::{self, EodConvention};
 use anyhow::Result;
+use chrono_tz::Tz;
+use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CashReport {
     pub account_id: String,
     pub currency: Currency,
     pub start_timestamp_ms: i64,
     pub end_timestamp_ms: i64,
 
-    pub starting_cash: f64,
-    pub ending_cash: f64,
-    pub ending_settled_cash: f64,
+    pub starting_cash: Money,
+    pub ending_cash: Money,
+    pub ending_settled_cash: Money,
 
-    pub net_trade_purchases: f64,
-    pub net_trade_sales: f64,
+    pub net_trade_purchases: Money,
+    pub net_trade_sales: Money,
 
-    pub commissions: f64,
-    pub commissions_mtd: Option<f64>,
-    pub commissions_ytd: Option<f64>,
+    pub commissions: Money,
+    pub commissions_mtd: Option<Money>,
+    pub commissions_ytd: Option<Money>,
 
-    pub other_fees: f64,
-    pub other_fees_mtd: Option<f64>,
-    pub other_fees_ytd: Option<f64>,
+    pub other_fees: Money,
+    pub other_fees_mtd: Option<Money>,
+    pub other_fees_ytd: Option<Money>,
 
-    pub dividends: f64,
-    pub dividends_mtd: Option<f64>,
-    pub dividends_ytd: Option<f64>,
+    pub broker_fees: Money,
+    pub broker_fees_mtd: Option<Money>,
+    pub broker_fees_ytd: Option<Money>,
 
-    pub interest: f64,
-    pub interest_mtd: Option<f64>,
-    pub interest_ytd: Option<f64>,
+    pub dividends: Money,
+    pub dividends_mtd: Option<Money>,
+    pub dividends_ytd: Option<Money>,
 
-    pub deposits: f64,
-    pub deposits_mtd: Option<f64>,
-    pub deposits_ytd: Option<f64>,
+    /// Net broker interest from `brokerInterest`: interest received less interest paid.
+    /// The cash report does not break it down further; margin interest paid makes it
+    /// negative. Individual interest postings appear in `stmt_funds` rows.
+    pub interest: Money,
+    pub interest_mtd: Option<Money>,
+    pub interest_ytd: Option<Money>,
 
-    pub withdrawals: f64,
-    pub withdrawals_mtd: Option<f64>,
-    pub withdrawals_ytd: Option<f64>,
+    pub deposits: Money,
+    pub deposits_mtd: Option<Money>,
+    pub deposits_ytd: Option<Money>,
+
+    pub withdrawals: Money,
+    pub withdrawals_mtd: Option<Money>,
+    pub withdrawals_ytd: Option<Money>,
 }
 
-impl StatementSection for CashReport {
-    fn from_node(node: &NodeWrapper) -> Result<CashReport> {
-        let start_date_eod_ms_plus_one = time_utils::trading_eod_after_hours_timestamp_ms(
-            node.node.attribute("fromDate").unwrap(),
-        )?;
+impl StatementSectionWithEod for CashReport {
+    fn from_node(
+        node: &impl AttributeSource,
+        _timezone_map: &HashMap<String, Tz>,
+        eod_convention: EodConvention,
+    ) -> Result<CashReport> {
+        let start_date_eod_ms_plus_one =
+            time_utils::trading_eod_timestamp_ms(&node.get_attribute("fromDate")?, eod_convention)?;
         let start_timestamp_ms = start_date_eod_ms_plus_one - (60 * 60 * 24 * 1000) + 1;
 
         Ok(CashReport {
             account_id: node.get_attribute("accountId")?,
-            currency: Currency::try_from(node.node.attribute("currency").unwrap())?,
+            currency: Currency::try_from(node.get_attribute("currency")?.as_str())?,
 
             starting_cash: node.parse_attribute("startingCash")?,
             ending_cash: node.parse_attribute("endingCash")?,
@@ -69,6 +83,10 @@ impl StatementSection for CashReport {
             other_fees_mtd: node.parse_attribute_opt("otherFeesMTD")?,
             other_fees_ytd: node.parse_attribute_opt("otherFeesYTD")?,
 
+            broker_fees: node.parse_attribute("brokerFees")?,
+            broker_fees_mtd: node.parse_attribute_opt("brokerFeesMTD")?,
+            broker_fees_ytd: node.parse_attribute_opt("brokerFeesYTD")?,
+
             dividends: node.parse_attribute("dividends")?,
             dividends_mtd: node.parse_attribute_opt("dividendsMTD")?,
             dividends_ytd: node.parse_attribute_opt("dividendsYTD")?,
@@ -86,8 +104,9 @@ impl StatementSection for CashReport {
             withdrawals_ytd: node.parse_attribute_opt("withdrawalsYTD")?,
 
             start_timestamp_ms,
-            end_timestamp_ms: time_utils::trading_eod_after_hours_timestamp_ms(
-                node.node.attribute("toDate").unwrap(),
+            end_timestamp_ms: time_utils::trading_eod_timestamp_ms(
+                &node.get_attribute("toDate")?,
+                eod_convention,
             )?,
         })
     }
@@ -98,6 +117,7 @@ mod tests {
     use super::*;
     use crate::Parser;
     use crate::currency::Currency;
+    use crate::money::money;
     use anyhow::Result;
 
     const PARTIAL_STATEMENT_EXAMPLE: &str = r##"
@@ -200,7 +220,7 @@ mod tests {
 
     #[test]
     fn cash_reports_parse() -> Result<()> {
-        let statements = Parser::new()?.parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
         assert_eq!(statements.len(), 1);
         let result = &statements[0];
 
@@ -213,36 +233,40 @@ mod tests {
                 account_id: "U1234567".to_string(),
                 currency: Currency::USD,
 
-                starting_cash: -1755658.754517244,
-                ending_cash: -1856140.999082752,
-                ending_settled_cash: -1755734.794082752,
+                starting_cash: money("-1755658.754517244"),
+                ending_cash: money("-1856140.999082752"),
+                ending_settled_cash: money("-1755734.794082752"),
+
+                commissions: money("-56.26956551"),
+                commissions_mtd: Some(money("-11167.4772929")),
+                commissions_ytd: Some(money("-25339.56064716")),
 
-                commissions: -56.26956551,
-                commissions_mtd: Some(-11167.4772929),
-                commissions_ytd: Some(-25339.56064716),
+                dividends: money("0.0"),
+                dividends_mtd: Some(money("0.0")),
+                dividends_ytd: Some(money("110.7")),
 
-                dividends: 0.0,
-                dividends_mtd: Some(0.0),
-                dividends_ytd: Some(110.7),
+                other_fees: money("-19.77"),
+                other_fees_mtd: Some(money("-121.27")),
+                other_fees_ytd: Some(money("-486.9")),
 
-                other_fees: -19.77,
-                other_fees_mtd: Some(-121.27),
-                other_fees_ytd: Some(-486.9),
+                broker_fees: money("0.0"),
+                broker_fees_mtd: Some(money("0.0")),
+                broker_fees_ytd: Some(money("0.0")),
 
-                net_trade_purchases: 0.0,
-                net_trade_sales: 0.0,
+                net_trade_purchases: money("0.0"),
+                net_trade_sales: money("0.0"),
 
-                interest: 0.0,
-                interest_mtd: Some(-545.49),
-                interest_ytd: Some(-1341.59),
+                interest: money("0.0"),
+                interest_mtd: Some(money("-545.49")),
+                interest_ytd: Some(money("-1341.59")),
 
-                deposits: 0.0,
-                deposits_mtd: Some(0.0),
-                deposits_ytd: Some(1650000.0),
+                deposits: money("0.0"),
+                deposits_mtd: Some(money("0.0")),
+                deposits_ytd: Some(money("1650000.0")),
 
-                withdrawals: 0.0,
-                withdrawals_mtd: Some(0.0),
-                withdrawals_ytd: Some(0.0),
+                withdrawals: money("0.0"),
+                withdrawals_mtd: Some(money("0.0")),
+                withdrawals_ytd: Some(money("0.0")),
 
                 start_timestamp_ms: result.cash_reports[0].start_timestamp_ms,
                 end_timestamp_ms: result.cash_reports[0].end_timestamp_ms,
@@ -255,7 +279,7 @@ mod tests {
     #[test]
     fn cash_reports_parse_no_mtd_ytd() -> Result<()> {
         let statements =
-            Parser::new()?.parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE_NO_MTD_YTD)?;
+            Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE_NO_MTD_YTD)?;
         assert_eq!(statements.len(), 1);
         let result = &statements[0];
 
@@ -268,34 +292,38 @@ mod tests {
                 account_id: "U1234567".to_string(),
                 currency: Currency::USD,
 
-                starting_cash: 1308.406411423,
-                ending_cash: 1308.406411423,
-                ending_settled_cash: 1308.406411423,
+                starting_cash: money("1308.406411423"),
+                ending_cash: money("1308.406411423"),
+                ending_settled_cash: money("1308.406411423"),
 
-                commissions: 0.0,
+                commissions: money("0.0"),
                 commissions_mtd: None,
                 commissions_ytd: None,
 
-                dividends: 0.0,
+                dividends: money("0.0"),
                 dividends_mtd: None,
                 dividends_ytd: None,
 
-                other_fees: 0.0,
+                other_fees: money("0.0"),
                 other_fees_mtd: None,
                 other_fees_ytd: None,
 
-                net_trade_purchases: 0.0,
-                net_trade_sales: 0.0,
+                broker_fees: money("0.0"),
+                broker_fees_mtd: None,
+                broker_fees_ytd: None,
 
-                interest: 0.0,
+                net_trade_purchases: money("0.0"),
+                net_trade_sales: money("0.0"),
+
+                interest: money("0.0"),
                 interest_mtd: None,
                 interest_ytd: None,
 
-                deposits: 0.0,
+                deposits: money("0.0"),
                 deposits_mtd: None,
                 deposits_ytd: None,
 
-                withdrawals: 0.0,
+                withdrawals: money("0.0"),
                 withdrawals_mtd: None,
                 withdrawals_ytd: None,
 
@@ -306,4 +334,83 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn cash_reports_excluding_base_skips_base_summary() -> Result<()> {
+        let statements =
+            Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE_NO_MTD_YTD)?;
+        let currencies: Vec<&Currency> = statements[0]
+            .cash_reports_excluding_base()
+            .into_iter()
+            .map(|r| &r.currency)
+            .collect();
+        assert_eq!(currencies, vec![&Currency::CAD, &Currency::USD]);
+        Ok(())
+    }
+
+    #[test]
+    fn cash_reports_by_currency_indexes_rows() -> Result<()> {
+        let statements =
+            Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE_NO_MTD_YTD)?;
+        let statement = &statements[0];
+
+        let by_currency = statement.cash_reports_by_currency();
+        assert_eq!(by_currency.len(), 3);
+        assert_eq!(by_currency[&Currency::CAD].starting_cash, money("0.001153"));
+        assert_eq!(
+            by_currency[&Currency::BASE].starting_cash,
+            money("1308.407281684")
+        );
+        assert_eq!(
+            statement.cash_report_for_currency(&Currency::USD),
+            Some(&statement.cash_reports[2])
+        );
+        assert_eq!(
+            statement.cash_report_for_currency(&Currency::Other("EUR".to_string())),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn net_deposits_by_currency_skips_base_summary() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE_NO_MTD_YTD
+            .replace(
+                r#"deposits="0" depositsSec"#,
+                r#"deposits="1650000" depositsSec"#,
+            )
+            .replace(
+                r#"withdrawals="0" withdrawalsSec"#,
+                r#"withdrawals="-150000" withdrawalsSec"#,
+            );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+
+        let net_deposits = statements[0].net_deposits_by_currency();
+        assert_eq!(net_deposits.len(), 2);
+        assert_eq!(net_deposits[&Currency::CAD], money("1500000"));
+        assert_eq!(net_deposits[&Currency::USD], money("1500000"));
+        Ok(())
+    }
+
+    #[test]
+    fn total_fees_sums_commissions_and_fees() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let total_fees = statements[0].total_fees();
+        assert_eq!(total_fees.len(), 1);
+        assert!((total_fees[&Currency::USD] - money("-76.03956551")).abs() < money("0.000001"));
+        Ok(())
+    }
+
+    #[test]
+    fn interest_is_net_of_margin_interest_paid() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE
+            .replace(r#"brokerInterest="0""#, r#"brokerInterest="-7012.37""#);
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let usd = statements[0]
+            .cash_report_for_currency(&Currency::USD)
+            .unwrap();
+        assert_eq!(usd.interest, money("-7012.37"));
+        assert_eq!(usd.interest_mtd, Some(money("-545.49")));
+        Ok(())
+    }
 }
@@ -0,0 +1,271 @@
+//! Client for IBKR's Flex Web Service.
+//!
+//! Fetching a statement is a two-step exchange: `SendRequest` asks IBKR to generate the
+//! statement for a saved Flex query and returns a reference code, then `GetStatement`
+//! downloads it once it is ready.
+
+use crate::error::FlexParseError;
+use crate::{Parser, Statement};
+use anyhow::Result;
+use roxmltree::Document;
+use std::thread;
+use std::time::Duration;
+
+const DEFAULT_BASE_URL: &str =
+    "https://ndcdyn.interactivebrokers.com/AccountManagement/FlexWebService";
+
+/// IBKR error code returned while a requested statement is still being generated.
+const STATEMENT_IN_PROGRESS_CODE: &str = "1019";
+
+/// Performs the HTTP GET requests made by [`FlexClient`].
+///
+/// The default implementation uses `reqwest`; tests and callers with their own HTTP stack
+/// can provide an alternative through [`FlexClient::with_transport`].
+pub trait FlexTransport {
+    fn get(&self, url: &str) -> Result<String>;
+}
+
+struct ReqwestTransport {
+    client: reqwest::blocking::Client,
+}
+
+impl FlexTransport for ReqwestTransport {
+    fn get(&self, url: &str) -> Result<String> {
+        Ok(self.client.get(url).send()?.error_for_status()?.text()?)
+    }
+}
+
+/// Downloads and parses statements from the Flex Web Service.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibkr_flex_statement::client::FlexClient;
+///
+/// let client = FlexClient::new("my-flex-token");
+/// let statements = client.fetch_statement("123456").unwrap();
+/// ```
+pub struct FlexClient {
+    token: String,
+    transport: Box<dyn FlexTransport>,
+    pub parser: Parser,
+    pub base_url: String,
+    /// Number of `GetStatement` attempts made while the statement is being generated.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled after each attempt.
+    pub initial_backoff: Duration,
+}
+
+/// The `FlexStatementResponse` document returned by `SendRequest`, and by `GetStatement`
+/// when no statement is available.
+enum ServiceResponse {
+    Success { reference_code: String, url: String },
+    Fail { code: String, message: String },
+}
+
+impl FlexClient {
+    pub fn new(token: &str) -> Self {
+        Self::with_transport(
+            token,
+            Box::new(ReqwestTransport {
+                client: reqwest::blocking::Client::new(),
+            }),
+        )
+    }
+
+    pub fn with_transport(token: &str, transport: Box<dyn FlexTransport>) -> Self {
+        FlexClient {
+            token: token.to_string(),
+            transport,
+            parser: Parser::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            max_attempts: 10,
+            initial_backoff: Duration::from_secs(5),
+        }
+    }
+
+    /// Requests the statement for a saved Flex query, waits for IBKR to generate it, and
+    /// parses the result.
+    pub fn fetch_statement(&self, query_id: &str) -> Result<Vec<Statement>> {
+        let send_url = format!(
+            "{}/SendRequest?t={}&q={}&v=3",
+            self.base_url, self.token, query_id
+        );
+        let response = self.transport.get(&send_url)?;
+        let (reference_code, url) = match parse_service_response(&response)? {
+            Some(ServiceResponse::Success {
+                reference_code,
+                url,
+            }) => (reference_code, url),
+            Some(ServiceResponse::Fail { code, message }) => {
+                return Err(service_error(&code, &message));
+            }
+            None => return Err(anyhow::Error::msg("unexpected SendRequest response")),
+        };
+
+        let get_url = format!("{}?t={}&q={}&v=3", url, self.token, reference_code);
+        let mut backoff = self.initial_backoff;
+        for attempt in 1..=self.max_attempts {
+            let body = self.transport.get(&get_url)?;
+            match parse_service_response(&body)? {
+                None => return self.parser.parse_flex_query_response(&body),
+                Some(ServiceResponse::Fail { code, .. }) if code == STATEMENT_IN_PROGRESS_CODE => {
+                    if attempt < self.max_attempts {
+                        thread::sleep(backoff);
+                        backoff *= 2;
+                    }
+                }
+                Some(ServiceResponse::Fail { code, message }) => {
+                    return Err(service_error(&code, &message));
+                }
+                Some(ServiceResponse::Success { .. }) => {
+                    return Err(anyhow::Error::msg("unexpected GetStatement response"));
+                }
+            }
+        }
+        Err(anyhow::Error::msg(format!(
+            "statement not ready after {} attempts",
+            self.max_attempts
+        )))
+    }
+}
+
+fn service_error(code: &str, message: &str) -> anyhow::Error {
+    FlexParseError::ServiceError {
+        code: code.to_string(),
+        message: message.to_string(),
+    }
+    .into()
+}
+
+/// Returns `None` when the body is not a `FlexStatementResponse`, i.e. it is the statement.
+fn parse_service_response(body: &str) -> Result<Option<ServiceResponse>> {
+    let doc = Document::parse(body)?;
+    let root = doc.root_element();
+    if root.tag_name().name() != "FlexStatementResponse" {
+        return Ok(None);
+    }
+
+    let child_text = |name: &str| {
+        root.children()
+            .find(|n| n.tag_name().name() == name)
+            .and_then(|n| n.text())
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+
+    if child_text("Status") == "Success" {
+        Ok(Some(ServiceResponse::Success {
+            reference_code: child_text("ReferenceCode"),
+            url: child_text("Url"),
+        }))
+    } else {
+        Ok(Some(ServiceResponse::Fail {
+            code: child_text("ErrorCode"),
+            message: child_text("ErrorMessage"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    const SEND_REQUEST_SUCCESS: &str = r##"
+        <FlexStatementResponse timestamp="26 April, 2025 01:34 PM EDT">
+            <Status>Success</Status>
+            <ReferenceCode>1234567890</ReferenceCode>
+            <Url>https://ndcdyn.interactivebrokers.com/AccountManagement/FlexWebService/GetStatement</Url>
+        </FlexStatementResponse>
+        "##;
+
+    const STATEMENT_IN_PROGRESS: &str = r##"
+        <FlexStatementResponse timestamp="26 April, 2025 01:34 PM EDT">
+            <Status>Warn</Status>
+            <ErrorCode>1019</ErrorCode>
+            <ErrorMessage>Statement generation in progress. Please try again shortly.</ErrorMessage>
+        </FlexStatementResponse>
+        "##;
+
+    const INVALID_TOKEN: &str = r##"
+        <FlexStatementResponse timestamp="26 April, 2025 01:34 PM EDT">
+            <Status>Fail</Status>
+            <ErrorCode>1012</ErrorCode>
+            <ErrorMessage>Token has expired.</ErrorMessage>
+        </FlexStatementResponse>
+        "##;
+
+    /// Replays canned responses in order and records the requested URLs.
+    struct RecordedTransport {
+        responses: RefCell<VecDeque<&'static str>>,
+        urls: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl FlexTransport for RecordedTransport {
+        fn get(&self, url: &str) -> Result<String> {
+            self.urls.borrow_mut().push(url.to_string());
+            self.responses
+                .borrow_mut()
+                .pop_front()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow::Error::msg("no more recorded responses"))
+        }
+    }
+
+    fn client_with(responses: Vec<&'static str>) -> (FlexClient, Rc<RefCell<Vec<String>>>) {
+        let urls = Rc::new(RefCell::new(Vec::new()));
+        let transport = RecordedTransport {
+            responses: RefCell::new(responses.into()),
+            urls: urls.clone(),
+        };
+        let mut client = FlexClient::with_transport("token", Box::new(transport));
+        client.initial_backoff = Duration::ZERO;
+        (client, urls)
+    }
+
+    #[test]
+    fn fetch_statement_retries_until_ready() -> Result<()> {
+        let (client, urls) = client_with(vec![
+            SEND_REQUEST_SUCCESS,
+            STATEMENT_IN_PROGRESS,
+            STATEMENT_IN_PROGRESS,
+            FULL_STATEMENT_EXAMPLE,
+        ]);
+
+        let statements = client.fetch_statement("987654")?;
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].trades.len(), 2);
+
+        let urls = urls.borrow();
+        assert_eq!(urls.len(), 4);
+        assert!(urls[0].ends_with("/SendRequest?t=token&q=987654&v=3"));
+        assert!(urls[1].ends_with("/GetStatement?t=token&q=1234567890&v=3"));
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_statement_reports_service_errors() {
+        let (client, _) = client_with(vec![INVALID_TOKEN]);
+        let error = client.fetch_statement("987654").unwrap_err();
+        assert!(error.to_string().contains("1012"));
+        assert!(error.to_string().contains("Token has expired."));
+    }
+
+    #[test]
+    fn fetch_statement_gives_up_after_max_attempts() {
+        let (mut client, urls) = client_with(vec![
+            SEND_REQUEST_SUCCESS,
+            STATEMENT_IN_PROGRESS,
+            STATEMENT_IN_PROGRESS,
+        ]);
+        client.max_attempts = 2;
+
+        assert!(client.fetch_statement("987654").is_err());
+        assert_eq!(urls.borrow().len(), 3);
+    }
+}
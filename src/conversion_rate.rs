@@ -0,0 +1,119 @@
+use crate::currency::Currency;
+use crate::money::Money;
+use crate::node_utils::AttributeSource;
+use crate::statement_section::StatementSectionWithEod;
+use crate::time_utils::{self, EodConvention};
+use anyhow::Result;
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+/// The rate converting `from_currency` into the account's base currency on a report date.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConversionRate {
+    pub from_currency: Currency,
+    pub rate: Money,
+    pub report_date: String,
+    pub timestamp_eod_ms: i64,
+    pub to_currency: Currency,
+}
+
+impl StatementSectionWithEod for ConversionRate {
+    fn from_node(
+        node: &impl AttributeSource,
+        _timezone_map: &HashMap<String, Tz>,
+        eod_convention: EodConvention,
+    ) -> Result<ConversionRate> {
+        let report_date = node.get_attribute("reportDate")?;
+        let timestamp_eod_ms = time_utils::trading_eod_timestamp_ms(&report_date, eod_convention)?;
+        Ok(ConversionRate {
+            from_currency: Currency::try_from(node.get_attribute("fromCurrency")?.as_str())?,
+            rate: node.parse_attribute("rate")?,
+            report_date,
+            timestamp_eod_ms,
+            to_currency: Currency::try_from(node.get_attribute("toCurrency")?.as_str())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::error::SectionError;
+    use crate::money::money;
+    use anyhow::Result;
+
+    const PARTIAL_STATEMENT_EXAMPLE: &str = r##"
+        <FlexQueryResponse queryName="example-query" type="AF">
+            <FlexStatements count="1">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-24" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
+                    <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks,Options,Warrants,Forex,Futures,Crypto Currencies,Mutual Funds,Fully Paid Stock Loan" />
+                    <ConversionRates>
+                        <ConversionRate reportDate="2025-04-24" fromCurrency="CAD" toCurrency="USD" rate="0.72088" />
+                        <ConversionRate reportDate="2025-04-25" fromCurrency="CAD" toCurrency="USD" rate="0.72155" />
+                        <ConversionRate
+                            reportDate="2025-04-25"
+                            fromCurrency="EUR"
+                            toCurrency="USD"
+                            rate="1.1369" />
+                    </ConversionRates>
+                </FlexStatement>
+            </FlexStatements>
+         </FlexQueryResponse>
+        "##;
+
+    #[test]
+    fn conversion_rates_parse() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        assert_eq!(statements.len(), 1);
+        let result = &statements[0];
+
+        assert_eq!(result.conversion_rates.len(), 3);
+
+        assert_eq!(
+            result.conversion_rates[2],
+            ConversionRate {
+                from_currency: Currency::Other("EUR".to_string()),
+                rate: money("1.1369"),
+                report_date: "2025-04-25".to_string(),
+                timestamp_eod_ms: result.conversion_rates[2].timestamp_eod_ms,
+                to_currency: Currency::USD,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn conversion_rate_missing_currency_is_an_error() {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(r#" toCurrency="USD""#, "", 1);
+        let error = Parser::new().parse_flex_query_response(&xml).unwrap_err();
+        let section_error = error.downcast_ref::<SectionError>().unwrap();
+        assert_eq!(section_error.tag_name, "ConversionRate");
+        assert_eq!(
+            section_error.error.to_string(),
+            "missing attribute toCurrency"
+        );
+    }
+
+    #[test]
+    fn to_base_currency_uses_latest_rate() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let converted = result
+            .to_base_currency(money("1000"), Currency::CAD)
+            .unwrap();
+        assert!((converted - money("721.55")).abs() < money("0.000001"));
+
+        assert_eq!(
+            result.to_base_currency(money("1000"), Currency::USD),
+            Some(money("1000"))
+        );
+        assert_eq!(
+            result.to_base_currency(money("1000"), Currency::Other("JPY".to_string())),
+            None
+        );
+        Ok(())
+    }
+}
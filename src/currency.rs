@@ -1,10 +1,14 @@
 use anyhow::Result;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Currency {
     BASE,
     CAD,
     USD,
+    Other(String),
 }
 
 impl<'a> TryFrom<&'a str> for Currency {
@@ -15,7 +19,49 @@ impl<'a> TryFrom<&'a str> for Currency {
             "BASE_SUMMARY" => Ok(Currency::BASE),
             "CAD" => Ok(Currency::CAD),
             "USD" => Ok(Currency::USD),
-            _ => Err(anyhow::Error::msg(format!("unknown currency {}", s))),
+            "" => Err(anyhow::Error::msg("empty currency")),
+            _ => Ok(Currency::Other(s.to_string())),
         }
     }
 }
+
+impl FromStr for Currency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Currency::try_from(s)
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Currency::BASE => f.write_str("BASE_SUMMARY"),
+            Currency::CAD => f.write_str("CAD"),
+            Currency::USD => f.write_str("USD"),
+            Currency::Other(s) => f.write_str(s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn currency_display_round_trips() -> Result<()> {
+        assert_eq!(Currency::USD.to_string(), "USD");
+        assert_eq!(Currency::BASE.to_string(), "BASE_SUMMARY");
+
+        for code in ["BASE_SUMMARY", "CAD", "USD", "EUR"] {
+            assert_eq!(code.parse::<Currency>()?.to_string(), code);
+        }
+        assert_eq!(
+            "EUR".parse::<Currency>()?,
+            Currency::Other("EUR".to_string())
+        );
+        assert!("".parse::<Currency>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+//! Conversion of trades and open positions into polars `DataFrame`s.
+
+use crate::money::Money;
+use crate::open_position::OpenPosition;
+use crate::trade::{Trade, TradeSide};
+use anyhow::Result;
+use polars::prelude::*;
+
+#[cfg(not(feature = "decimal"))]
+fn float(value: Money) -> f64 {
+    value
+}
+
+#[cfg(feature = "decimal")]
+fn float(value: Money) -> f64 {
+    use rust_decimal::prelude::ToPrimitive;
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+fn datetime_column(name: &str, timestamps_ms: Vec<i64>) -> Column {
+    Int64Chunked::from_vec(name.into(), timestamps_ms)
+        .into_datetime(TimeUnit::Milliseconds, None)
+        .into_series()
+        .into()
+}
+
+pub(crate) fn trades_dataframe<'a>(
+    trades: impl IntoIterator<Item = &'a Trade>,
+) -> Result<DataFrame> {
+    let trades: Vec<&Trade> = trades.into_iter().collect();
+    let side = |t: &&Trade| match t.side {
+        TradeSide::Buy => "BUY",
+        TradeSide::Sell => "SELL",
+    };
+
+    Ok(DataFrame::new(vec![
+        Column::new(
+            "account_id".into(),
+            trades
+                .iter()
+                .map(|t| t.account_id.as_str())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "ticker".into(),
+            trades.iter().map(|t| t.ticker.as_str()).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "conid".into(),
+            trades.iter().map(|t| t.conid).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "currency".into(),
+            trades
+                .iter()
+                .map(|t| t.currency.to_string())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new("side".into(), trades.iter().map(side).collect::<Vec<_>>()),
+        Column::new(
+            "quantity".into(),
+            trades.iter().map(|t| t.quantity).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "price".into(),
+            trades.iter().map(|t| float(t.price)).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "trade_money".into(),
+            trades
+                .iter()
+                .map(|t| float(t.trade_money))
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "commission".into(),
+            trades
+                .iter()
+                .map(|t| float(t.commission))
+                .collect::<Vec<_>>(),
+        ),
+        datetime_column(
+            "execution_time",
+            trades.iter().map(|t| t.execution_timestamp_ms).collect(),
+        ),
+    ])?)
+}
+
+pub(crate) fn open_positions_dataframe(positions: &[OpenPosition]) -> Result<DataFrame> {
+    Ok(DataFrame::new(vec![
+        Column::new(
+            "account_id".into(),
+            positions
+                .iter()
+                .map(|p| p.account_id.as_str())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "ticker".into(),
+            positions
+                .iter()
+                .map(|p| p.ticker.as_str())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "conid".into(),
+            positions.iter().map(|p| p.conid).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "currency".into(),
+            positions
+                .iter()
+                .map(|p| p.currency.to_string())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "open_quantity".into(),
+            positions
+                .iter()
+                .map(|p| p.open_quantity)
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "cost_basis_price".into(),
+            positions
+                .iter()
+                .map(|p| float(p.cost_basis_price))
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "mark_price".into(),
+            positions
+                .iter()
+                .map(|p| p.mark_price.map(float))
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "position_value".into(),
+            positions
+                .iter()
+                .map(|p| p.position_value.map(float))
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "fifo_pnl_unrealized".into(),
+            positions
+                .iter()
+                .map(|p| float(p.fifo_pnl_unrealized))
+                .collect::<Vec<_>>(),
+        ),
+        datetime_column(
+            "report_time",
+            positions.iter().map(|p| p.timestamp_eod_ms).collect(),
+        ),
+    ])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Parser;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+    use anyhow::Result;
+    use polars::prelude::*;
+
+    #[test]
+    fn trades_dataframe_has_one_row_per_trade() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let df = statements[0].trades_dataframe()?;
+
+        assert_eq!(df.shape(), (2, 10));
+        let tickers = df.column("ticker")?.as_materialized_series().str()?.clone();
+        assert_eq!(tickers.get(1), Some("GEO"));
+        let prices = df.column("price")?.as_materialized_series().f64()?.clone();
+        assert_eq!(prices.get(0), Some(606.57));
+        assert_eq!(
+            df.column("execution_time")?.dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, None)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn open_positions_dataframe_has_one_row_per_position() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let df = statements[0].open_positions_dataframe()?;
+
+        assert_eq!(df.shape(), (7, 10));
+        let tickers = df.column("ticker")?.as_materialized_series().str()?.clone();
+        assert_eq!(tickers.get(6), Some("TTWO"));
+        let marks = df
+            .column("mark_price")?
+            .as_materialized_series()
+            .f64()?
+            .clone();
+        assert_eq!(marks.get(6), Some(225.38));
+        Ok(())
+    }
+}
@@ -1,51 +1,104 @@
 use super::currency::Currency;
-use super::time_utils;
-use crate::node_utils::NodeWrapper;
-use crate::statement_section::StatementSection;
+use super::time_utils::{self, EodConvention};
+use crate::error::ValidationError;
+use crate::money::Money;
+use crate::node_utils::AttributeSource;
+use crate::statement_section::StatementSectionWithEod;
 use anyhow::Result;
+use chrono_tz::Tz;
+use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EquitySummary {
     pub account_id: String,
-    pub cash_balance: f64,
-    pub cash_balance_long: f64,
-    pub cash_balance_short: f64,
+    pub cash_balance: Money,
+    pub cash_balance_long: Money,
+    pub cash_balance_short: Money,
+    pub commodities: Money,
+    pub commodities_long: Money,
+    pub commodities_short: Money,
     pub currency: Currency,
-    pub interest_accrual_mtd: f64,
-    pub interest_accrual_mtd_long: f64,
-    pub interest_accrual_mtd_short: f64,
-    pub stock_balance: f64,
-    pub stock_balance_long: f64,
-    pub stock_balance_short: f64,
+    pub dividend_accruals: Money,
+    pub funds: Money,
+    pub funds_long: Money,
+    pub funds_short: Money,
+    pub interest_accrual_mtd: Money,
+    pub interest_accrual_mtd_long: Money,
+    pub interest_accrual_mtd_short: Money,
+    pub report_date: String,
+    pub stock_balance: Money,
+    pub stock_balance_long: Money,
+    pub stock_balance_short: Money,
     pub timestamp_eod_ms: i64,
+    pub total: Money,
+    pub total_long: Money,
+    pub total_short: Money,
 }
 
-impl StatementSection for EquitySummary {
-    fn from_node(node: &NodeWrapper) -> Result<EquitySummary> {
+impl StatementSectionWithEod for EquitySummary {
+    fn from_node(
+        node: &impl AttributeSource,
+        _timezone_map: &HashMap<String, Tz>,
+        eod_convention: EodConvention,
+    ) -> Result<EquitySummary> {
+        let report_date = node.get_attribute("reportDate")?;
+        let timestamp_eod_ms = time_utils::trading_eod_timestamp_ms(&report_date, eod_convention)?;
         Ok(EquitySummary {
             account_id: node.get_attribute("accountId")?,
             cash_balance: node.parse_attribute("cash")?,
             cash_balance_long: node.parse_attribute("cashLong")?,
             cash_balance_short: node.parse_attribute("cashShort")?,
-            currency: Currency::try_from(node.node.attribute("currency").unwrap())?,
+            commodities: node.parse_attribute("commodities")?,
+            commodities_long: node.parse_attribute("commoditiesLong")?,
+            commodities_short: node.parse_attribute("commoditiesShort")?,
+            currency: Currency::try_from(node.get_attribute("currency")?.as_str())?,
+            dividend_accruals: node.parse_attribute("dividendAccruals")?,
+            funds: node.parse_attribute("funds")?,
+            funds_long: node.parse_attribute("fundsLong")?,
+            funds_short: node.parse_attribute("fundsShort")?,
             interest_accrual_mtd: node.parse_attribute("interestAccruals")?,
             interest_accrual_mtd_long: node.parse_attribute("interestAccrualsLong")?,
             interest_accrual_mtd_short: node.parse_attribute("interestAccrualsShort")?,
+            report_date,
             stock_balance: node.parse_attribute("stock")?,
             stock_balance_long: node.parse_attribute("stockLong")?,
             stock_balance_short: node.parse_attribute("stockShort")?,
-            timestamp_eod_ms: time_utils::trading_eod_after_hours_timestamp_ms(
-                node.node.attribute("reportDate").unwrap(),
-            )?,
+            timestamp_eod_ms,
+            total: node.parse_attribute("total")?,
+            total_long: node.parse_attribute("totalLong")?,
+            total_short: node.parse_attribute("totalShort")?,
         })
     }
 }
 
+impl EquitySummary {
+    /// Checks that `total` equals the sum of cash, stock, funds, commodities, dividend
+    /// accruals, and interest accruals to within `epsilon`.
+    pub fn validate(&self, epsilon: Money) -> Result<(), ValidationError> {
+        let computed = self.cash_balance
+            + self.stock_balance
+            + self.funds
+            + self.commodities
+            + self.dividend_accruals
+            + self.interest_accrual_mtd;
+        if (self.total - computed).abs() > epsilon {
+            return Err(ValidationError::EquityTotalMismatch {
+                timestamp_eod_ms: self.timestamp_eod_ms,
+                reported: self.total,
+                computed,
+            });
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Parser;
     use crate::currency::Currency;
+    use crate::money::money;
     use anyhow::Result;
 
     const PARTIAL_STATEMENT_EXAMPLE: &str = r##"
@@ -94,7 +147,7 @@ mod tests {
 
     #[test]
     fn equity_summaries_parse() -> Result<()> {
-        let statements = Parser::new()?.parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
         assert_eq!(statements.len(), 1);
         let result = &statements[0];
 
@@ -105,19 +158,79 @@ mod tests {
             result.equity_summaries[1],
             EquitySummary {
                 account_id: "U1234567".to_string(),
-                cash_balance: -1856140.99825062,
-                cash_balance_long: 0.000832132,
-                cash_balance_short: -1856140.999082752,
+                cash_balance: money("-1856140.99825062"),
+                cash_balance_long: money("0.000832132"),
+                cash_balance_short: money("-1856140.999082752"),
+                commodities: money("0"),
+                commodities_long: money("0"),
+                commodities_short: money("0"),
                 currency: Currency::USD,
-                interest_accrual_mtd: 1051.42,
-                interest_accrual_mtd_long: 1591.34,
-                interest_accrual_mtd_short: -539.92,
-                stock_balance: 3664457.0,
-                stock_balance_long: 3664457.0,
-                stock_balance_short: 0.0,
+                dividend_accruals: money("0"),
+                funds: money("0"),
+                funds_long: money("0"),
+                funds_short: money("0"),
+                interest_accrual_mtd: money("1051.42"),
+                interest_accrual_mtd_long: money("1591.34"),
+                interest_accrual_mtd_short: money("-539.92"),
+                stock_balance: money("3664457.0"),
+                stock_balance_long: money("3664457.0"),
+                stock_balance_short: money("0.0"),
+                report_date: "2025-04-25".to_string(),
                 timestamp_eod_ms: result.equity_summaries[1].timestamp_eod_ms,
+                total: money("1809367.421749379"),
+                total_long: money("3666048.340832131"),
+                total_short: money("-1856680.919082752"),
             }
         );
         Ok(())
     }
+
+    #[test]
+    fn equity_summary_commodities_and_funds_parse() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE
+            .replacen(r#"commodities="0""#, r#"commodities="1250.5""#, 1)
+            .replacen(r#"commoditiesLong="0""#, r#"commoditiesLong="2000.5""#, 1)
+            .replacen(r#"commoditiesShort="0""#, r#"commoditiesShort="-750""#, 1)
+            .replacen(r#"funds="0""#, r#"funds="10400""#, 1)
+            .replacen(r#"fundsLong="0""#, r#"fundsLong="10400""#, 1);
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let summary = &statements[0].equity_summaries[0];
+
+        assert_eq!(summary.commodities, money("1250.5"));
+        assert_eq!(summary.commodities_long, money("2000.5"));
+        assert_eq!(summary.commodities_short, money("-750"));
+        assert_eq!(summary.funds, money("10400"));
+        assert_eq!(summary.funds_long, money("10400"));
+        assert_eq!(summary.funds_short, money("0"));
+        Ok(())
+    }
+
+    #[test]
+    fn equity_summaries_validate() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        for summary in &statements[0].equity_summaries {
+            summary.validate(money("0.01"))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn equity_summary_with_wrong_total_fails_validation() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"total="1809367.421749379""#,
+            r#"total="1809467.421749379""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let summary = &statements[0].equity_summaries[1];
+
+        match summary.validate(money("0.01")) {
+            Err(ValidationError::EquityTotalMismatch { reported, .. }) => {
+                assert_eq!(reported, money("1809467.421749379"));
+            }
+            other => panic!("expected a total mismatch, got {:?}", other),
+        }
+        assert!(summary.validate(money("1000")).is_ok());
+        Ok(())
+    }
 }
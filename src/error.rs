@@ -0,0 +1,169 @@
+use crate::money::Money;
+use crate::position_discrepancy::PositionDiscrepancy;
+use roxmltree::Node;
+use std::fmt::{Display, Formatter};
+
+/// Errors describing why a Flex query response could not be parsed.
+///
+/// These are returned inside `anyhow::Error` and can be recovered with `downcast_ref`.
+#[derive(Debug, PartialEq)]
+pub enum FlexParseError {
+    /// IBKR returned a `FlexStatementResponse` error document instead of a statement.
+    ServiceError { code: String, message: String },
+    /// A `FlexStatements` element holds a different number of statements than its `count`
+    /// attribute declares, e.g. because the download was truncated.
+    StatementCountMismatch { declared: usize, found: usize },
+}
+
+impl Display for FlexParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlexParseError::ServiceError { code, message } => {
+                write!(f, "Flex Web Service error {}: {}", code, message)
+            }
+            FlexParseError::StatementCountMismatch { declared, found } => write!(
+                f,
+                "FlexStatements declares {} statements but contains {}",
+                declared, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FlexParseError {}
+
+/// A statement row that failed to parse, recorded by the lenient parse mode and returned,
+/// wrapped in `anyhow::Error`, by the strict one.
+#[derive(Debug)]
+pub struct SectionError {
+    pub tag_name: String,
+    pub attributes: Vec<(String, String)>,
+    /// The source text of the offending element, e.g. `<Trade accountId="U1234567" ... />`,
+    /// for logging or showing to a user.
+    pub outer_xml: String,
+    pub error: anyhow::Error,
+}
+
+impl SectionError {
+    pub(crate) fn new(node: &Node, error: anyhow::Error) -> Self {
+        SectionError {
+            tag_name: node.tag_name().name().to_string(),
+            attributes: node
+                .attributes()
+                .map(|a| (a.name().to_string(), a.value().to_string()))
+                .collect(),
+            outer_xml: node.document().input_text()[node.range()].to_string(),
+            error,
+        }
+    }
+}
+
+impl Display for SectionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to parse {}: {}", self.tag_name, self.error)
+    }
+}
+
+impl std::error::Error for SectionError {}
+
+/// A parsed row whose values are inconsistent with each other.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    /// An equity summary's `total` differs from the sum of its components.
+    EquityTotalMismatch {
+        timestamp_eod_ms: i64,
+        reported: Money,
+        computed: Money,
+    },
+    /// A FIFO performance summary's `total_realized_pnl` differs from the sum of its
+    /// short- and long-term realized profits and losses.
+    FifoRealizedMismatch {
+        ticker: Option<String>,
+        reported: Money,
+        computed: Money,
+    },
+    /// A FIFO performance summary's `total_fifo_pnl` differs from its realized PnL plus its
+    /// unrealized profits and losses.
+    FifoTotalMismatch {
+        ticker: Option<String>,
+        reported: Money,
+        computed: Money,
+    },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::EquityTotalMismatch {
+                timestamp_eod_ms,
+                reported,
+                computed,
+            } => write!(
+                f,
+                "equity summary at {} reports total {} but its components sum to {}",
+                timestamp_eod_ms, reported, computed
+            ),
+            ValidationError::FifoRealizedMismatch {
+                ticker,
+                reported,
+                computed,
+            } => write!(
+                f,
+                "FIFO performance summary {} reports realized PnL {} but its components sum to {}",
+                ticker.as_deref().unwrap_or("(total)"),
+                reported,
+                computed
+            ),
+            ValidationError::FifoTotalMismatch {
+                ticker,
+                reported,
+                computed,
+            } => write!(
+                f,
+                "FIFO performance summary {} reports total PnL {} but its components sum to {}",
+                ticker.as_deref().unwrap_or("(total)"),
+                reported,
+                computed
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// A non-fatal integrity problem found by [`crate::Statement::validate`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationWarning {
+    /// A row's `account_id` differs from its statement's.
+    AccountIdMismatch { section: String, account_id: String },
+    /// A stock's open position quantity disagrees with its net stock position.
+    PositionMismatch(PositionDiscrepancy),
+    /// A row's values are inconsistent with each other.
+    Inconsistent(ValidationError),
+    /// A trade key, see [`crate::trade::Trade::dedup_key`], appears on more than one trade.
+    DuplicateTradeId(String),
+}
+
+impl Display for ValidationWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationWarning::AccountIdMismatch {
+                section,
+                account_id,
+            } => write!(
+                f,
+                "{} row belongs to account {} rather than the statement's",
+                section, account_id
+            ),
+            ValidationWarning::PositionMismatch(d) => write!(
+                f,
+                "{} open positions hold {} but net stock positions {}",
+                d.ticker, d.open_quantity, d.net_shares
+            ),
+            ValidationWarning::Inconsistent(error) => error.fmt(f),
+            ValidationWarning::DuplicateTradeId(id) => {
+                write!(f, "trade {} appears more than once", id)
+            }
+        }
+    }
+}
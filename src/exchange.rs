@@ -0,0 +1,161 @@
+use std::fmt::{Display, Formatter};
+
+/// A trading venue, parsed from IBKR's exchange codes such as `exchange` and
+/// `listingExchange`. Codes without a variant are kept as [`Exchange::Other`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Exchange {
+    Amex,
+    Arca,
+    Bats,
+    Bex,
+    Byx,
+    EdgeA,
+    Edgx,
+    Iex,
+    /// Nasdaq's INET book, which IBKR reports separately from `NASDAQ`.
+    Island,
+    Lse,
+    Memx,
+    Nasdaq,
+    Nyse,
+    Psx,
+    Tse,
+    Other(String),
+}
+
+impl From<&str> for Exchange {
+    fn from(s: &str) -> Self {
+        match s {
+            "AMEX" => Self::Amex,
+            "ARCA" => Self::Arca,
+            "BATS" => Self::Bats,
+            "BEX" => Self::Bex,
+            "BYX" => Self::Byx,
+            "EDGEA" => Self::EdgeA,
+            "EDGX" => Self::Edgx,
+            "IEX" => Self::Iex,
+            "ISLAND" => Self::Island,
+            "LSE" => Self::Lse,
+            "MEMX" => Self::Memx,
+            "NASDAQ" => Self::Nasdaq,
+            "NYSE" => Self::Nyse,
+            "PSX" => Self::Psx,
+            "TSE" => Self::Tse,
+            _ => Self::Other(s.to_string()),
+        }
+    }
+}
+
+impl Exchange {
+    /// Returns IBKR's code for the venue, e.g. `BYX`.
+    pub fn code(&self) -> &str {
+        match self {
+            Self::Amex => "AMEX",
+            Self::Arca => "ARCA",
+            Self::Bats => "BATS",
+            Self::Bex => "BEX",
+            Self::Byx => "BYX",
+            Self::EdgeA => "EDGEA",
+            Self::Edgx => "EDGX",
+            Self::Iex => "IEX",
+            Self::Island => "ISLAND",
+            Self::Lse => "LSE",
+            Self::Memx => "MEMX",
+            Self::Nasdaq => "NASDAQ",
+            Self::Nyse => "NYSE",
+            Self::Psx => "PSX",
+            Self::Tse => "TSE",
+            Self::Other(s) => s,
+        }
+    }
+
+    /// Returns a human-readable name for the venue, or the raw code for
+    /// [`Exchange::Other`].
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Amex => "NYSE American",
+            Self::Arca => "NYSE Arca",
+            Self::Bats => "Cboe BZX",
+            Self::Bex => "Nasdaq BX",
+            Self::Byx => "Cboe BYX",
+            Self::EdgeA => "Cboe EDGA",
+            Self::Edgx => "Cboe EDGX",
+            Self::Iex => "Investors Exchange",
+            Self::Island => "Nasdaq (INET)",
+            Self::Lse => "London Stock Exchange",
+            Self::Memx => "Members Exchange",
+            Self::Nasdaq => "Nasdaq",
+            Self::Nyse => "New York Stock Exchange",
+            Self::Psx => "Nasdaq PSX",
+            Self::Tse => "Toronto Stock Exchange",
+            Self::Other(s) => s,
+        }
+    }
+
+    /// Returns the ISO 10383 market identifier code of the venue, or `None` for
+    /// [`Exchange::Other`].
+    pub fn mic(&self) -> Option<&'static str> {
+        match self {
+            Self::Amex => Some("XASE"),
+            Self::Arca => Some("ARCX"),
+            Self::Bats => Some("BATS"),
+            Self::Bex => Some("XBOS"),
+            Self::Byx => Some("BATY"),
+            Self::EdgeA => Some("EDGA"),
+            Self::Edgx => Some("EDGX"),
+            Self::Iex => Some("IEXG"),
+            Self::Island | Self::Nasdaq => Some("XNAS"),
+            Self::Lse => Some("XLON"),
+            Self::Memx => Some("MEMX"),
+            Self::Nyse => Some("XNYS"),
+            Self::Psx => Some("XPHL"),
+            Self::Tse => Some("XTSE"),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl Display for Exchange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+    use anyhow::Result;
+
+    #[test]
+    fn exchange_codes_map_to_names_and_mics() {
+        assert_eq!(Exchange::from("BYX"), Exchange::Byx);
+        assert_eq!(Exchange::Byx.name(), "Cboe BYX");
+        assert_eq!(Exchange::Byx.mic(), Some("BATY"));
+
+        assert_eq!(Exchange::from("NYSE"), Exchange::Nyse);
+        assert_eq!(Exchange::Nyse.name(), "New York Stock Exchange");
+        assert_eq!(Exchange::Nyse.mic(), Some("XNYS"));
+
+        let other = Exchange::from("DRCTEDGE");
+        assert_eq!(other, Exchange::Other("DRCTEDGE".to_string()));
+        assert_eq!(other.name(), "DRCTEDGE");
+        assert_eq!(other.mic(), None);
+
+        for code in ["BYX", "ISLAND", "NYSE", "DRCTEDGE"] {
+            assert_eq!(Exchange::from(code).to_string(), code);
+        }
+    }
+
+    #[test]
+    fn trade_venues_parse() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let trade = &statements[0].trades[0];
+        assert_eq!(trade.execution_exchange, "BYX");
+        assert_eq!(trade.execution_venue(), Exchange::Byx);
+        assert_eq!(trade.listing_venue(), Exchange::Nasdaq);
+        Ok(())
+    }
+}
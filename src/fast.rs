@@ -0,0 +1,191 @@
+//! A `quick-xml` pull-parser backend for [`Parser::parse_flex_query_response`] that reads
+//! the response in one pass instead of loading it into a DOM.
+
+use crate::error::{FlexParseError, SectionError};
+use crate::node_utils::{AttributeSource, StartTag};
+use crate::section_set::SectionSet;
+use crate::statement::Statement;
+use crate::{Parser, StatementRows, check_account_id_offenders, parse_document};
+use anyhow::{Context, Result};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+/// A `FlexStatement` whose rows are still being read.
+struct OpenStatement {
+    account_id: String,
+    rows: StatementRows,
+    /// Rows whose account id differs from the statement's, for `strict_account_ids`.
+    offenders: Vec<String>,
+    /// The first row that failed to parse. It is reported once the statement ends, after
+    /// any account id mismatch, matching the order of checks in the DOM backend.
+    error: Option<SectionError>,
+    /// The depth and start offset of the failed row while its element is still open, so
+    /// its `outer_xml` can be extended to the end tag.
+    open_error_row: Option<(usize, usize)>,
+}
+
+impl OpenStatement {
+    fn new(start: &StartTag) -> Self {
+        OpenStatement {
+            account_id: start.attribute("accountId").unwrap_or_default().to_string(),
+            rows: StatementRows::default(),
+            offenders: Vec::new(),
+            error: None,
+            open_error_row: None,
+        }
+    }
+
+    /// Adds a row read from `xml[start..end]`, its start tag, or its whole element if empty.
+    /// `depth` is the depth of a non-empty element, whose end tag is still to come.
+    fn push(
+        &mut self,
+        parser: &Parser,
+        row: &StartTag,
+        xml: &str,
+        (start, end): (usize, usize),
+        depth: Option<usize>,
+    ) {
+        if let Some(account_id) = row
+            .attribute("accountId")
+            .filter(|id| *id != self.account_id)
+        {
+            self.offenders
+                .push(format!("{} ({})", row.tag_name(), account_id));
+        }
+        if let Err(error) = self.rows.push(row, SectionSet::ALL, parser)
+            && self.error.is_none()
+        {
+            self.error = Some(row.section_error(&xml[start..end], error));
+            self.open_error_row = depth.map(|depth| (depth, start));
+        }
+    }
+
+    /// Completes the failed row's `outer_xml` once the element ending at `xml[..end]` at
+    /// `depth` closes it.
+    fn close(&mut self, xml: &str, depth: usize, end: usize) {
+        if let (Some(error), Some((row_depth, start))) = (&mut self.error, self.open_error_row)
+            && row_depth == depth
+        {
+            error.outer_xml = xml[start..end].to_string();
+            self.open_error_row = None;
+        }
+    }
+
+    fn finish(self, parser: &Parser) -> Result<Statement> {
+        if parser.strict_account_ids {
+            check_account_id_offenders(&self.account_id, &self.offenders)?;
+        }
+        match self.error {
+            Some(error) => Err(error.into()),
+            None => self.rows.into_statement(Some(&self.account_id)),
+        }
+    }
+}
+
+/// Parses a Flex query response, returning the same statements as the DOM backend.
+///
+/// Rows are parsed straight from their start tags' attributes, so no DOM is built. Malformed
+/// XML fails with a [`quick_xml::Error`] carrying the byte offset of the error.
+pub(crate) fn parse_flex_query_response(
+    parser: &Parser,
+    flex_query_response: &str,
+) -> Result<Vec<Statement>> {
+    let xml = flex_query_response
+        .strip_prefix('\u{FEFF}')
+        .unwrap_or(flex_query_response);
+    let mut reader = Reader::from_str(xml);
+
+    let mut statements = Vec::new();
+    let mut statement: Option<OpenStatement> = None;
+    let mut declared_count: Option<usize> = None;
+    let mut found_count = 0;
+    let mut depth = 0usize;
+
+    loop {
+        let event_start = reader.buffer_position() as usize;
+        let event = match reader.read_event() {
+            Ok(event) => event,
+            Err(error) => {
+                let position = reader.error_position();
+                return Err(error).with_context(|| format!("malformed XML at byte {}", position));
+            }
+        };
+        let (start, is_empty) = match event {
+            Event::Start(start) => {
+                depth += 1;
+                (start, false)
+            }
+            Event::Empty(start) => (start, true),
+            Event::End(end) => {
+                if let Some(open) = statement.as_mut() {
+                    open.close(xml, depth, reader.buffer_position() as usize);
+                }
+                depth = depth.saturating_sub(1);
+                match end.local_name().as_ref() {
+                    b"FlexStatement" => {
+                        if let Some(open) = statement.take() {
+                            statements.push(open.finish(parser)?);
+                        }
+                    }
+                    b"FlexStatements" => check_count(declared_count.take(), found_count)?,
+                    _ => {}
+                }
+                continue;
+            }
+            Event::Eof => {
+                if depth > 0 {
+                    return Err(anyhow::Error::msg(
+                        "malformed XML: unexpected end of document",
+                    ));
+                }
+                break;
+            }
+            _ => continue,
+        };
+
+        match start.local_name().as_ref() {
+            // Service error documents are small; let the DOM backend report them.
+            b"FlexStatementResponse" => return parse_document(xml).map(|_| Vec::new()),
+            b"FlexStatements" => {
+                declared_count = start_tag(&reader, &start)?.parse_attribute_opt("count")?;
+                found_count = 0;
+                if is_empty {
+                    check_count(declared_count.take(), found_count)?;
+                }
+            }
+            b"FlexStatement" => {
+                found_count += 1;
+                let open = OpenStatement::new(&start_tag(&reader, &start)?);
+                if is_empty {
+                    statements.push(open.finish(parser)?);
+                } else {
+                    statement = Some(open);
+                }
+            }
+            _ => {
+                if let Some(open) = statement.as_mut() {
+                    let span = (event_start, reader.buffer_position() as usize);
+                    let row_depth = (!is_empty).then_some(depth);
+                    open.push(parser, &start_tag(&reader, &start)?, xml, span, row_depth);
+                }
+            }
+        }
+    }
+
+    Ok(statements)
+}
+
+/// Reads a start tag's attributes, reporting malformed ones like other malformed XML.
+fn start_tag<'a>(reader: &Reader<&[u8]>, start: &'a BytesStart) -> Result<StartTag<'a>> {
+    StartTag::new(start)
+        .with_context(|| format!("malformed XML at byte {}", reader.buffer_position()))
+}
+
+fn check_count(declared: Option<usize>, found: usize) -> Result<()> {
+    match declared {
+        Some(declared) if declared != found => {
+            Err(FlexParseError::StatementCountMismatch { declared, found }.into())
+        }
+        _ => Ok(()),
+    }
+}
@@ -1,43 +1,91 @@
-use crate::statement_section::StatementSection;
+use crate::statement_section::StatementSectionWithEod;
 
-use super::node_utils::NodeWrapper;
-use super::time_utils;
+use super::node_utils::AttributeSource;
+use super::time_utils::{self, EodConvention};
+use crate::asset_category::AssetCategory;
+use crate::currency::Currency;
+use crate::error::ValidationError;
+use crate::money::Money;
 use anyhow::Result;
+use chrono_tz::Tz;
+use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FIFOPerformanceSummary {
     pub account_id: String,
+    pub report_date: String,
     pub timestamp_eod_ms: i64,
 
+    /// `None` for the aggregate row, which leaves `assetCategory` empty.
+    pub asset_category: Option<AssetCategory>,
     pub ticker: Option<String>,
-    pub conid: Option<u32>,
+    pub conid: Option<u64>,
     pub listing_exchange: Option<String>,
+    /// The row's currency, when the query reports one; summaries in base currency may omit it.
+    pub currency: Option<Currency>,
 
-    pub realized_st_profit: f64,
-    pub realized_st_loss: f64,
-    pub unrealized_st_profit: f64,
-    pub unrealized_st_loss: f64,
+    pub realized_st_profit: Money,
+    pub realized_st_loss: Money,
+    pub unrealized_st_profit: Money,
+    pub unrealized_st_loss: Money,
 
-    pub realized_lt_profit: f64,
-    pub realized_lt_loss: f64,
-    pub unrealized_lt_profit: f64,
-    pub unrealized_lt_loss: f64,
+    pub realized_lt_profit: Money,
+    pub realized_lt_loss: Money,
+    pub unrealized_lt_profit: Money,
+    pub unrealized_lt_loss: Money,
 
-    pub total_realized_pnl: f64,
-    pub total_fifo_pnl: f64,
+    pub total_realized_pnl: Money,
+    pub total_fifo_pnl: Money,
 }
 
-impl StatementSection for FIFOPerformanceSummary {
-    fn from_node(node: &NodeWrapper) -> Result<FIFOPerformanceSummary> {
+/// Realized short- and long-term gains and losses for one symbol, summed across its FIFO
+/// performance summaries.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RealizedSummary {
+    pub st_profit: Money,
+    pub st_loss: Money,
+    pub lt_profit: Money,
+    pub lt_loss: Money,
+    pub st_total: Money,
+    pub lt_total: Money,
+    pub total: Money,
+}
+
+impl RealizedSummary {
+    pub(crate) fn add(&mut self, summary: &FIFOPerformanceSummary) {
+        self.st_profit += summary.realized_st_profit;
+        self.st_loss += summary.realized_st_loss;
+        self.lt_profit += summary.realized_lt_profit;
+        self.lt_loss += summary.realized_lt_loss;
+        self.st_total += summary.realized_st_profit + summary.realized_st_loss;
+        self.lt_total += summary.realized_lt_profit + summary.realized_lt_loss;
+        self.total += summary.total_realized_pnl;
+    }
+}
+
+impl StatementSectionWithEod for FIFOPerformanceSummary {
+    fn from_node(
+        node: &impl AttributeSource,
+        _timezone_map: &HashMap<String, Tz>,
+        eod_convention: EodConvention,
+    ) -> Result<FIFOPerformanceSummary> {
+        let report_date = node.get_attribute("reportDate")?;
+        let timestamp_eod_ms = time_utils::trading_eod_timestamp_ms(&report_date, eod_convention)?;
         Ok(FIFOPerformanceSummary {
             account_id: node.get_attribute("accountId")?,
-            timestamp_eod_ms: time_utils::trading_eod_after_hours_timestamp_ms(
-                node.node.attribute("reportDate").unwrap(),
-            )?,
+            report_date,
+            timestamp_eod_ms,
 
+            asset_category: node.parse_attribute_opt("assetCategory")?,
             ticker: node.get_attribute_opt("symbol"),
             conid: node.parse_attribute_opt("conid")?,
             listing_exchange: node.get_attribute_opt("listingExchange"),
+            currency: node
+                .get_attribute_opt("currency")
+                .map(|s| Currency::try_from(s.as_str()))
+                .transpose()?,
 
             realized_st_profit: node.parse_attribute("realizedSTProfit")?,
             realized_st_loss: node.parse_attribute("realizedSTLoss")?,
@@ -55,10 +103,44 @@ impl StatementSection for FIFOPerformanceSummary {
     }
 }
 
+impl FIFOPerformanceSummary {
+    /// Checks, to within `epsilon`, that `total_realized_pnl` equals the realized short- and
+    /// long-term profits and losses, and that `total_fifo_pnl` equals the realized PnL plus
+    /// the unrealized profits and losses.
+    pub fn validate(&self, epsilon: Money) -> Result<(), ValidationError> {
+        let realized = self.realized_st_profit
+            + self.realized_st_loss
+            + self.realized_lt_profit
+            + self.realized_lt_loss;
+        if (self.total_realized_pnl - realized).abs() > epsilon {
+            return Err(ValidationError::FifoRealizedMismatch {
+                ticker: self.ticker.clone(),
+                reported: self.total_realized_pnl,
+                computed: realized,
+            });
+        }
+
+        let total = self.total_realized_pnl
+            + self.unrealized_st_profit
+            + self.unrealized_st_loss
+            + self.unrealized_lt_profit
+            + self.unrealized_lt_loss;
+        if (self.total_fifo_pnl - total).abs() > epsilon {
+            return Err(ValidationError::FifoTotalMismatch {
+                ticker: self.ticker.clone(),
+                reported: self.total_fifo_pnl,
+                computed: total,
+            });
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Parser;
+    use crate::money::money;
     use anyhow::Result;
 
     const PARTIAL_STATEMENT_EXAMPLE: &str = r##"
@@ -122,7 +204,7 @@ mod tests {
 
     #[test]
     fn fifo_performance_summaries_parse() -> Result<()> {
-        let statements = Parser::new()?.parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
         assert_eq!(statements.len(), 1);
         let result = &statements[0];
 
@@ -133,20 +215,23 @@ mod tests {
             result.fifo_performance_summaries[8],
             FIFOPerformanceSummary {
                 account_id: "U1234567".to_string(),
+                asset_category: Some(AssetCategory::Stock),
                 ticker: Some("TTWO".to_string()),
                 conid: Some(6478131),
                 listing_exchange: Some("NASDAQ".to_string()),
+                currency: None,
+                report_date: "2025-04-25".to_string(),
                 timestamp_eod_ms: result.fifo_performance_summaries[8].timestamp_eod_ms,
-                realized_st_profit: 0.0,
-                realized_st_loss: 0.0,
-                unrealized_st_profit: 4089.983554,
-                unrealized_st_loss: 0.0,
-                realized_lt_profit: 0.0,
-                realized_lt_loss: 0.0,
-                unrealized_lt_profit: 0.0,
-                unrealized_lt_loss: 0.0,
-                total_realized_pnl: 0.0,
-                total_fifo_pnl: 4089.983554,
+                realized_st_profit: money("0.0"),
+                realized_st_loss: money("0.0"),
+                unrealized_st_profit: money("4089.983554"),
+                unrealized_st_loss: money("0.0"),
+                realized_lt_profit: money("0.0"),
+                realized_lt_loss: money("0.0"),
+                unrealized_lt_profit: money("0.0"),
+                unrealized_lt_loss: money("0.0"),
+                total_realized_pnl: money("0.0"),
+                total_fifo_pnl: money("4089.983554"),
             }
         );
 
@@ -154,22 +239,116 @@ mod tests {
             result.fifo_performance_summaries[9],
             FIFOPerformanceSummary {
                 account_id: "U1234567".to_string(),
+                asset_category: None,
                 ticker: None,
                 conid: None,
                 listing_exchange: None,
+                currency: None,
+                report_date: "2025-04-25".to_string(),
                 timestamp_eod_ms: result.fifo_performance_summaries[9].timestamp_eod_ms,
-                realized_st_profit: 0.0,
-                realized_st_loss: -205.04987357,
-                unrealized_st_profit: 131057.571473,
-                unrealized_st_loss: -44834.337024864,
-                realized_lt_profit: 0.0,
-                realized_lt_loss: 0.0,
-                unrealized_lt_profit: 0.0,
-                unrealized_lt_loss: 0.0,
-                total_realized_pnl: -205.04987357,
-                total_fifo_pnl: 86018.184574566,
+                realized_st_profit: money("0.0"),
+                realized_st_loss: money("-205.04987357"),
+                unrealized_st_profit: money("131057.571473"),
+                unrealized_st_loss: money("-44834.337024864"),
+                realized_lt_profit: money("0.0"),
+                realized_lt_loss: money("0.0"),
+                unrealized_lt_profit: money("0.0"),
+                unrealized_lt_loss: money("0.0"),
+                total_realized_pnl: money("-205.04987357"),
+                total_fifo_pnl: money("86018.184574566"),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fifo_performance_summary_currency_parses() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"symbol="TTWO""#,
+            r#"symbol="TTWO" currency="USD""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let summaries = &statements[0].fifo_performance_summaries;
+        assert_eq!(summaries[8].currency, Some(Currency::USD));
+        assert_eq!(summaries[9].currency, None);
+        Ok(())
+    }
+
+    #[test]
+    fn fifo_performance_summary_asset_categories_parse() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        for summary in &statements[0].fifo_performance_summaries {
+            let expected = summary.ticker.as_ref().map(|_| AssetCategory::Stock);
+            assert_eq!(summary.asset_category, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn total_realized_pnl_matches_aggregate_row() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        // ARGX + GEO + GRPN + TSLA, which matches the empty-symbol aggregate row.
+        let total = result.total_realized_pnl();
+        assert!((total - money("-205.04987357")).abs() < money("0.000001"));
+        assert!(
+            (total - result.fifo_performance_summaries[9].total_realized_pnl).abs()
+                < money("0.000001")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fifo_performance_summaries_validate() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let summaries = &statements[0].fifo_performance_summaries;
+        assert!(summaries.iter().any(|s| s.ticker.is_none()));
+        for summary in summaries {
+            summary.validate(money("0.01"))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fifo_performance_summary_with_wrong_totals_fails_validation() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let mut summary = statements[0].fifo_performance_summaries[0].clone();
+
+        summary.total_fifo_pnl += money("1");
+        assert!(matches!(
+            summary.validate(money("0.01")),
+            Err(ValidationError::FifoTotalMismatch { .. })
+        ));
+
+        summary.total_realized_pnl += money("1");
+        assert!(matches!(
+            summary.validate(money("0.01")),
+            Err(ValidationError::FifoRealizedMismatch { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn realized_summary_by_symbol_groups_rows() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let by_symbol = statements[0].realized_summary_by_symbol();
+
+        assert!(!by_symbol.contains_key(""));
+        assert_eq!(
+            by_symbol["GEO"],
+            RealizedSummary {
+                st_profit: money("0"),
+                st_loss: money("-1375.50915991"),
+                lt_profit: money("0"),
+                lt_loss: money("0"),
+                st_total: money("-1375.50915991"),
+                lt_total: money("0"),
+                total: money("-1375.50915991"),
             }
         );
+        assert_eq!(by_symbol["NFLX"].total, money("0"));
         Ok(())
     }
 }
@@ -0,0 +1,64 @@
+use crate::statement::Statement;
+
+/// The `type` of a Flex query response.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResponseType {
+    /// An activity statement, `type="AF"`.
+    Activity,
+    /// A trade confirmation report, `type="TF"`.
+    TradeConfirmation,
+    Other(String),
+}
+
+impl From<&str> for ResponseType {
+    fn from(s: &str) -> Self {
+        match s {
+            "AF" => Self::Activity,
+            "TF" => Self::TradeConfirmation,
+            _ => Self::Other(s.to_string()),
+        }
+    }
+}
+
+/// A parsed Flex query response: its statements along with the attributes of the
+/// `FlexQueryResponse` element describing the query that produced them.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlexResponse {
+    /// The name of the saved Flex query, from `queryName`.
+    pub query_name: Option<String>,
+    /// The report format, from `type`.
+    pub response_type: Option<ResponseType>,
+    pub statements: Vec<Statement>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+    use anyhow::Result;
+
+    #[test]
+    fn flex_response_keeps_query_attributes() -> Result<()> {
+        let parser = Parser::new();
+        let response = parser.parse_flex_response(FULL_STATEMENT_EXAMPLE)?;
+        assert_eq!(response.query_name.as_deref(), Some("example-query"));
+        assert_eq!(response.response_type, Some(ResponseType::Activity));
+        assert_eq!(
+            response.statements,
+            parser.parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn response_type_parses() {
+        assert_eq!(ResponseType::from("TF"), ResponseType::TradeConfirmation);
+        assert_eq!(
+            ResponseType::from("XX"),
+            ResponseType::Other("XX".to_string())
+        );
+    }
+}
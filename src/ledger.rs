@@ -0,0 +1,157 @@
+use crate::cash_report::CashReport;
+use crate::currency::Currency;
+use crate::money::Money;
+use crate::trade::{Trade, TradeSide};
+
+/// One cash movement in a statement's ledger. Created by [`crate::Statement::cash_ledger`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LedgerEntry {
+    pub timestamp_ms: i64,
+    pub description: String,
+    /// Signed cash impact: positive for cash received, negative for cash paid.
+    pub amount: Money,
+    pub currency: Currency,
+}
+
+/// Builds the ledger from the execution-level trades and the per-currency cash reports,
+/// ordered by timestamp. Rows sharing a timestamp keep the order they were added in.
+pub(crate) fn cash_ledger<'a>(
+    trades: impl Iterator<Item = &'a Trade>,
+    cash_reports: impl Iterator<Item = &'a CashReport>,
+) -> Vec<LedgerEntry> {
+    let mut entries: Vec<LedgerEntry> = trades.flat_map(trade_entries).collect();
+    for report in cash_reports {
+        let movements = [
+            ("Dividends", report.dividends),
+            ("Broker interest", report.interest),
+            ("Other fees", report.other_fees),
+            ("Broker fees", report.broker_fees),
+            ("Deposits", report.deposits),
+            ("Withdrawals", report.withdrawals),
+        ];
+        entries.extend(
+            movements
+                .into_iter()
+                .filter(|(_, amount)| *amount != Money::default())
+                .map(|(description, amount)| LedgerEntry {
+                    timestamp_ms: report.end_timestamp_ms,
+                    description: description.to_string(),
+                    amount,
+                    currency: report.currency.clone(),
+                }),
+        );
+    }
+    entries.sort_by_key(|e| e.timestamp_ms);
+    entries
+}
+
+/// Returns the trade's net cash, or its proceeds and a separate commission entry when the
+/// commission is charged in another currency.
+fn trade_entries(trade: &Trade) -> Vec<LedgerEntry> {
+    let side = match trade.side {
+        TradeSide::Buy => "BUY",
+        TradeSide::Sell => "SELL",
+    };
+    let cancel = if trade.is_cancel { " (cancelled)" } else { "" };
+    let description = format!(
+        "{} {} {}{}",
+        side,
+        trade.quantity.abs(),
+        trade.ticker,
+        cancel
+    );
+    if trade.commission_currency == trade.currency {
+        return vec![LedgerEntry {
+            timestamp_ms: trade.execution_timestamp_ms,
+            description,
+            amount: trade.net_proceeds(),
+            currency: trade.currency.clone(),
+        }];
+    }
+    vec![
+        LedgerEntry {
+            timestamp_ms: trade.execution_timestamp_ms,
+            description: description.clone(),
+            amount: trade.proceeds,
+            currency: trade.currency.clone(),
+        },
+        LedgerEntry {
+            timestamp_ms: trade.execution_timestamp_ms,
+            description: format!("Commission {}", description),
+            amount: trade.commission,
+            currency: trade.commission_currency.clone(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::money::money;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+    use anyhow::Result;
+
+    #[test]
+    fn cash_ledger_combines_trades_and_cash_reports() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let statement = &statements[0];
+        let ledger = statement.cash_ledger();
+
+        let descriptions: Vec<&str> = ledger.iter().map(|e| e.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            vec!["BUY 1 ARGX", "BUY 1000 GEO", "Other fees"]
+        );
+
+        assert_eq!(
+            ledger[0].timestamp_ms,
+            statement.trades[0].execution_timestamp_ms
+        );
+        assert!((ledger[0].amount - money("-607.570035")).abs() < money("0.000001"));
+        assert!((ledger[1].amount - money("-30855.035")).abs() < money("0.000001"));
+
+        assert_eq!(
+            ledger[2],
+            LedgerEntry {
+                timestamp_ms: statement.cash_reports[2].end_timestamp_ms,
+                description: "Other fees".to_string(),
+                amount: money("-19.77"),
+                currency: Currency::USD,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cash_ledger_splits_commission_in_another_currency() -> Result<()> {
+        let xml = FULL_STATEMENT_EXAMPLE.replacen(
+            r#"ibCommission="-1.000035" ibCommissionCurrency="USD""#,
+            r#"ibCommission="-1.35" ibCommissionCurrency="CAD""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let ledger = statements[0].cash_ledger();
+
+        let timestamp_ms = statements[0].trades[0].execution_timestamp_ms;
+        assert_eq!(
+            ledger[..2],
+            [
+                LedgerEntry {
+                    timestamp_ms,
+                    description: "BUY 1 ARGX".to_string(),
+                    amount: money("-606.57"),
+                    currency: Currency::USD,
+                },
+                LedgerEntry {
+                    timestamp_ms,
+                    description: "Commission BUY 1 ARGX".to_string(),
+                    amount: money("-1.35"),
+                    currency: Currency::CAD,
+                },
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,41 +1,65 @@
 pub mod account_info;
+pub mod aggregated_order;
+pub mod approx_eq;
 pub mod asset_category;
+#[cfg(feature = "beancount")]
+pub mod beancount;
 pub mod cash_report;
+#[cfg(feature = "network")]
+pub mod client;
+pub mod conversion_rate;
 pub mod currency;
+#[cfg(feature = "polars")]
+mod dataframe;
 pub mod equity_summary;
+pub mod error;
+pub mod exchange;
+#[cfg(feature = "fast")]
+mod fast;
 pub mod fifo_performance_summary;
+pub mod flex_response;
+pub mod ledger;
+pub mod money;
 pub mod net_stock_position;
 mod node_utils;
 pub mod open_position;
+pub mod position_discrepancy;
+pub mod prelude;
+pub mod section_set;
+pub mod statement;
+pub mod statement_ref;
 pub mod statement_section;
-mod time_utils;
+pub mod stmt_funds;
+#[cfg(feature = "streaming")]
+mod streaming;
+pub mod time_utils;
 pub mod trade;
+pub mod trade_confirm;
 
-use account_info::AccountInfo;
 use anyhow::Result;
-use cash_report::CashReport;
 use chrono_tz::Tz;
-use equity_summary::EquitySummary;
-use fifo_performance_summary::FIFOPerformanceSummary;
-use net_stock_position::NetStockPosition;
-use node_utils::NodeWrapper;
-use open_position::OpenPosition;
+use error::{FlexParseError, SectionError};
+use flex_response::{FlexResponse, ResponseType};
+use node_utils::{AttributeSource, NodeWrapper};
 use roxmltree::{Document, Node};
-use statement_section::{StatementSection, StatementSectionWithTimezone};
+use statement_section::{StatementSection, StatementSectionWithEod, StatementSectionWithTimezone};
 use std::collections::HashMap;
-use std::fmt::Debug;
-use trade::Trade;
-
-#[derive(Debug, PartialEq)]
-pub struct Statement {
-    pub account_info: AccountInfo,
-    pub cash_reports: Vec<CashReport>,
-    pub equity_summaries: Vec<EquitySummary>,
-    pub fifo_performance_summaries: Vec<FIFOPerformanceSummary>,
-    pub net_stock_positions: Vec<NetStockPosition>,
-    pub open_positions: Vec<OpenPosition>,
-    pub trades: Vec<Trade>,
-}
+use time_utils::EodConvention;
+
+pub use account_info::{AccountInfo, BaseCurrency};
+pub use aggregated_order::AggregatedOrder;
+pub use cash_report::CashReport;
+pub use conversion_rate::ConversionRate;
+pub use equity_summary::EquitySummary;
+pub use fifo_performance_summary::FIFOPerformanceSummary;
+pub use net_stock_position::NetStockPosition;
+pub use open_position::OpenPosition;
+pub use position_discrepancy::PositionDiscrepancy;
+pub use section_set::SectionSet;
+pub use statement::Statement;
+pub use stmt_funds::FundsLine;
+pub use trade::Trade;
+pub use trade_confirm::TradeConfirm;
 
 /// Parser for interpreting the content of an InteractiveBrokers Flex-based XML statement.
 ///
@@ -47,86 +71,549 @@ pub struct Statement {
 ///
 /// let statement_xml: &str = "<FlexQueryResponse>...</FlexQueryResponse>";
 ///
-/// let parser = Parser::new().unwrap();
+/// let parser = Parser::new();
 /// let statements: Vec<Statement> = parser.parse_flex_query_response(statement_xml).unwrap();
 /// ```
 pub struct Parser {
     pub timezone_map: HashMap<String, Tz>,
+    /// When set, every row's `accountId` must match its `FlexStatement`'s `accountId`, and a
+    /// statement with mismatched rows fails to parse. Defaults to `false`.
+    pub strict_account_ids: bool,
+    /// Which close end-of-day timestamps, such as `timestamp_eod_ms` and cash report
+    /// periods, refer to. Defaults to [`EodConvention::AfterHours`].
+    pub eod_convention: EodConvention,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Parser {
-    pub fn new() -> Result<Self> {
-        let new_york_tz: Tz = "America/New_York".parse().unwrap();
+    pub fn new() -> Self {
+        let new_york_tz = chrono_tz::America::New_York;
         let timezone_map = HashMap::from([
             ("EST".to_string(), new_york_tz),
             ("EDT".to_string(), new_york_tz),
         ]);
 
-        Ok(Parser { timezone_map })
+        Parser {
+            timezone_map,
+            strict_account_ids: false,
+            eod_convention: EodConvention::AfterHours,
+        }
+    }
+
+    fn check_account_ids(&self, node: &Node) -> Result<()> {
+        let statement_account_id = node.attribute("accountId").unwrap_or_default();
+        let offenders = node
+            .descendants()
+            .filter_map(|n| {
+                n.attribute("accountId")
+                    .filter(|id| *id != statement_account_id)
+                    .map(|id| format!("{} ({})", n.tag_name().name(), id))
+            })
+            .collect::<Vec<String>>();
+        check_account_id_offenders(statement_account_id, &offenders)
     }
 
-    fn parse_section<T: StatementSection>(
+    fn parse_flex_statement(
         &self,
         node: &Node,
-        section_name: &str,
-    ) -> Result<Vec<T>> {
-        node.descendants()
-            .filter(|n| n.tag_name().name() == section_name)
-            .map(|n| T::from_node(&NodeWrapper { node: n }).map_err(anyhow::Error::msg))
-            .collect::<Result<Vec<T>>>()
+        sections: SectionSet,
+        mut errors: Option<&mut Vec<SectionError>>,
+    ) -> Result<Statement> {
+        #[cfg(feature = "tracing")]
+        let _statement_span = tracing::trace_span!(
+            "parse_flex_statement",
+            account_id = node.attribute("accountId").unwrap_or_default()
+        )
+        .entered();
+
+        if self.strict_account_ids {
+            self.check_account_ids(node)?;
+        }
+
+        let mut rows = StatementRows::presized(node, sections);
+
+        // Walk each section once, routing its rows to their section by tag name. Rows such as
+        // `AccountInformation` sit directly in the statement and form their own section.
+        for section in node.children().filter(|n| n.is_element()) {
+            #[cfg(feature = "tracing")]
+            let _section_span = tracing::trace_span!(
+                "parse_section",
+                section = section.tag_name().name(),
+                account_id = node.attribute("accountId").unwrap_or_default()
+            )
+            .entered();
+
+            for n in section.descendants() {
+                let parsed = rows.push(&NodeWrapper { node: n }, sections, self);
+
+                // In lenient mode a bad row is recorded and skipped; otherwise it aborts the
+                // parse.
+                if let Err(error) = parsed {
+                    let error = SectionError::new(&n, error);
+                    match errors.as_deref_mut() {
+                        Some(errors) => errors.push(error),
+                        None => return Err(error.into()),
+                    }
+                }
+            }
+        }
+
+        rows.into_statement(node.attribute("accountId"))
+    }
+
+    /// Parses a Flex query response into one [`Statement`] per `FlexStatement`.
+    ///
+    /// With the `fast` feature the response is read with a `quick-xml` pull parser instead
+    /// of being loaded into a DOM; the statements returned are the same.
+    ///
+    /// Malformed XML fails with a [`roxmltree::Error`] whose message includes the line and
+    /// column of the problem, or, with the `fast` feature, with an error whose message starts
+    /// with `malformed XML` and gives the byte offset of the problem.
+    pub fn parse_flex_query_response(&self, flex_query_response: &str) -> Result<Vec<Statement>> {
+        #[cfg(feature = "fast")]
+        return fast::parse_flex_query_response(self, flex_query_response);
+        #[cfg(not(feature = "fast"))]
+        self.parse_flex_query_response_dom(flex_query_response)
     }
 
-    fn parse_section_with_timezone<T: StatementSectionWithTimezone>(
+    /// Parses a Flex query response with the `roxmltree` DOM backend, even when the `fast`
+    /// feature selects the pull parser for [`Parser::parse_flex_query_response`], e.g. to
+    /// compare the two.
+    pub fn parse_flex_query_response_dom(
         &self,
-        node: &Node,
-        section_name: &str,
-    ) -> Result<Vec<T>> {
-        node.descendants()
-            .filter(|n| n.tag_name().name() == section_name)
-            .map(|n| {
-                T::from_node(&NodeWrapper { node: n }, &self.timezone_map)
-                    .map_err(anyhow::Error::msg)
+        flex_query_response: &str,
+    ) -> Result<Vec<Statement>> {
+        let doc = parse_document(flex_query_response)?;
+        flex_statement_nodes(&doc)?
+            .into_iter()
+            .map(|n| self.parse_flex_statement(&n, SectionSet::ALL, None))
+            .collect::<Result<Vec<Statement>>>()
+    }
+
+    /// Parses a Flex query response like [`Parser::parse_flex_query_response`], also
+    /// returning the `queryName` and `type` of the `FlexQueryResponse` element.
+    pub fn parse_flex_response(&self, flex_query_response: &str) -> Result<FlexResponse> {
+        let doc = parse_document(flex_query_response)?;
+        let root = NodeWrapper {
+            node: doc.root_element(),
+        };
+        let statements = flex_statement_nodes(&doc)?
+            .into_iter()
+            .map(|n| self.parse_flex_statement(&n, SectionSet::ALL, None))
+            .collect::<Result<Vec<Statement>>>()?;
+        Ok(FlexResponse {
+            query_name: root.get_attribute_opt("queryName"),
+            response_type: root
+                .get_attribute_opt("type")
+                .map(|s| ResponseType::from(s.as_str())),
+            statements,
+        })
+    }
+
+    /// Parses a Flex query response and groups its statements by account id.
+    ///
+    /// Consolidated queries can contain one `FlexStatement` per account. When an account
+    /// appears in more than one `FlexStatement`, those statements are combined with
+    /// [`Statement::merge`].
+    pub fn parse_by_account(
+        &self,
+        flex_query_response: &str,
+    ) -> Result<HashMap<String, Statement>> {
+        let mut by_account: HashMap<String, Vec<Statement>> = HashMap::new();
+        for statement in self.parse_flex_query_response(flex_query_response)? {
+            by_account
+                .entry(statement.account_id().to_string())
+                .or_default()
+                .push(statement);
+        }
+        by_account
+            .into_iter()
+            .map(|(account_id, statements)| Ok((account_id, Statement::merge(statements)?)))
+            .collect()
+    }
+
+    /// Parses a Flex query response, populating only the requested sections.
+    ///
+    /// Sections not in `sections` are skipped entirely and left empty on each statement.
+    pub fn parse_selected(
+        &self,
+        flex_query_response: &str,
+        sections: SectionSet,
+    ) -> Result<Vec<Statement>> {
+        let doc = parse_document(flex_query_response)?;
+        flex_statement_nodes(&doc)?
+            .into_iter()
+            .map(|n| self.parse_flex_statement(&n, sections, None))
+            .collect::<Result<Vec<Statement>>>()
+    }
+
+    /// Parses a Flex query response, skipping rows that fail to parse instead of aborting.
+    ///
+    /// Each skipped row is returned as a [`SectionError`] alongside the statements. Malformed
+    /// XML and missing account information are still reported as errors.
+    pub fn parse_flex_query_response_lenient(
+        &self,
+        flex_query_response: &str,
+    ) -> Result<(Vec<Statement>, Vec<SectionError>)> {
+        let doc = parse_document(flex_query_response)?;
+        let mut errors = Vec::new();
+        let statements = flex_statement_nodes(&doc)?
+            .into_iter()
+            .map(|n| self.parse_flex_statement(&n, SectionSet::ALL, Some(&mut errors)))
+            .collect::<Result<Vec<Statement>>>()?;
+        Ok((statements, errors))
+    }
+
+    /// Reads a Flex query response from `reader` and parses it.
+    ///
+    /// Failures can be told apart with `downcast_ref`: reading fails with a
+    /// [`std::io::Error`], which may be worth retrying, while input that is not UTF-8,
+    /// reported with a [`std::string::FromUtf8Error`], malformed XML, reported as by
+    /// [`Parser::parse_flex_query_response`], and invalid rows, reported with a
+    /// [`SectionError`], will not succeed on a retry.
+    pub fn parse_reader<R: std::io::Read>(&self, reader: R) -> Result<Vec<Statement>> {
+        self.parse_flex_query_response(&read_flex_query_response(reader)?)
+    }
+
+    /// Reads the Flex query response stored at `path` and parses it. Errors are reported as
+    /// by [`Parser::parse_reader`].
+    pub fn parse_file<P: AsRef<std::path::Path>>(&self, path: P) -> Result<Vec<Statement>> {
+        self.parse_reader(std::fs::File::open(path)?)
+    }
+
+    /// Parses each file in `paths` as by [`Parser::parse_file`], spreading the files across
+    /// one thread per available CPU. Results are returned in the order of `paths`, and a file
+    /// that fails to read or parse does not affect the others.
+    pub fn parse_paths(&self, paths: &[std::path::PathBuf]) -> Vec<Result<Vec<Statement>>> {
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = paths.len().div_ceil(workers).max(1);
+        std::thread::scope(|scope| {
+            let handles = paths
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|path| self.parse_file(path))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        })
+    }
+
+    /// Decompresses a gzip stream, such as an `.xml.gz` file, and parses the Flex query
+    /// response it contains.
+    #[cfg(feature = "gzip")]
+    pub fn parse_gzip<R: std::io::Read>(&self, reader: R) -> Result<Vec<Statement>> {
+        self.parse_reader(flate2::read::GzDecoder::new(reader))
+    }
+
+    /// Iterates over the `Trade` rows of a Flex query response as they are read, without
+    /// building the statements, so very large trade sections need not fit in memory.
+    ///
+    /// Other sections are skipped. A row that fails to parse is yielded as an error;
+    /// malformed XML ends the iteration after its error.
+    #[cfg(feature = "streaming")]
+    pub fn trade_iter<'a, R: std::io::Read + 'a>(
+        &'a self,
+        reader: R,
+    ) -> impl Iterator<Item = Result<Trade>> + 'a {
+        streaming::TradeIter::new(std::io::BufReader::new(reader), &self.timezone_map)
+    }
+}
+
+/// The rows of one `FlexStatement`, collected section by section.
+#[derive(Default)]
+struct StatementRows {
+    account_infos: Vec<AccountInfo>,
+    base_currencies: Vec<BaseCurrency>,
+    cash_reports: Vec<CashReport>,
+    conversion_rates: Vec<ConversionRate>,
+    equity_summaries: Vec<EquitySummary>,
+    fifo_performance_summaries: Vec<FIFOPerformanceSummary>,
+    funds_lines: Vec<FundsLine>,
+    net_stock_positions: Vec<NetStockPosition>,
+    open_positions: Vec<OpenPosition>,
+    trades: Vec<Trade>,
+    trade_confirms: Vec<TradeConfirm>,
+}
+
+impl StatementRows {
+    /// Creates the section vectors pre-sized from the row counts of the statement's section
+    /// wrapper elements, such as `<Trades>`, so that large sections are not regrown while
+    /// they are parsed.
+    fn presized(statement: &Node, sections: SectionSet) -> Self {
+        let mut rows = StatementRows::default();
+        for wrapper in statement.children().filter(Node::is_element) {
+            let count = || wrapper.children().filter(Node::is_element).count();
+            match wrapper.tag_name().name() {
+                "CashReport" if sections.contains(SectionSet::CASH_REPORTS) => {
+                    rows.cash_reports.reserve(count())
+                }
+                "ConversionRates" if sections.contains(SectionSet::CONVERSION_RATES) => {
+                    rows.conversion_rates.reserve(count())
+                }
+                "EquitySummaryInBase" if sections.contains(SectionSet::EQUITY_SUMMARIES) => {
+                    rows.equity_summaries.reserve(count())
+                }
+                "FIFOPerformanceSummaryInBase"
+                    if sections.contains(SectionSet::FIFO_PERFORMANCE_SUMMARIES) =>
+                {
+                    rows.fifo_performance_summaries.reserve(count())
+                }
+                "StmtFunds" if sections.contains(SectionSet::FUNDS_LINES) => {
+                    rows.funds_lines.reserve(count())
+                }
+                "NetStockPositionSummary" if sections.contains(SectionSet::NET_STOCK_POSITIONS) => {
+                    rows.net_stock_positions.reserve(count())
+                }
+                "OpenPositions" if sections.contains(SectionSet::OPEN_POSITIONS) => {
+                    rows.open_positions.reserve(count())
+                }
+                "Trades" if sections.contains(SectionSet::TRADES) => rows.trades.reserve(count()),
+                "TradeConfirms" if sections.contains(SectionSet::TRADE_CONFIRMS) => {
+                    rows.trade_confirms.reserve(count())
+                }
+                _ => {}
+            }
+        }
+        rows
+    }
+
+    /// Parses `row` into its section by tag name. Tags of sections outside `sections`, and
+    /// elements that are not rows, are skipped.
+    fn push(
+        &mut self,
+        row: &impl AttributeSource,
+        sections: SectionSet,
+        parser: &Parser,
+    ) -> Result<()> {
+        let timezone_map = &parser.timezone_map;
+        let eod_convention = parser.eod_convention;
+        match row.tag_name() {
+            "AccountInformation" => {
+                traced(row, || AccountInfo::from_node(row)).map(|r| self.account_infos.push(r))
+            }
+            "AccountSummary" => traced(row, || BaseCurrency::from_node(row, timezone_map))
+                .map(|r| self.base_currencies.push(r)),
+            "CashReportCurrency" if sections.contains(SectionSet::CASH_REPORTS) => {
+                traced(row, || {
+                    CashReport::from_node(row, timezone_map, eod_convention)
+                })
+                .map(|r| self.cash_reports.push(r))
+            }
+            "ConversionRate" if sections.contains(SectionSet::CONVERSION_RATES) => {
+                traced(row, || {
+                    ConversionRate::from_node(row, timezone_map, eod_convention)
+                })
+                .map(|r| self.conversion_rates.push(r))
+            }
+            "EquitySummaryByReportDateInBase"
+                if sections.contains(SectionSet::EQUITY_SUMMARIES) =>
+            {
+                traced(row, || {
+                    EquitySummary::from_node(row, timezone_map, eod_convention)
+                })
+                .map(|r| self.equity_summaries.push(r))
+            }
+            "FIFOPerformanceSummaryUnderlying"
+                if sections.contains(SectionSet::FIFO_PERFORMANCE_SUMMARIES) =>
+            {
+                traced(row, || {
+                    FIFOPerformanceSummary::from_node(row, timezone_map, eod_convention)
+                })
+                .map(|r| self.fifo_performance_summaries.push(r))
+            }
+            "StatementOfFundsLine" if sections.contains(SectionSet::FUNDS_LINES) => {
+                traced(row, || {
+                    FundsLine::from_node(row, timezone_map, eod_convention)
+                })
+                .map(|r| self.funds_lines.push(r))
+            }
+            "NetStockPosition" if sections.contains(SectionSet::NET_STOCK_POSITIONS) => {
+                traced(row, || NetStockPosition::from_node(row))
+                    .map(|r| self.net_stock_positions.push(r))
+            }
+            "OpenPosition" if sections.contains(SectionSet::OPEN_POSITIONS) => traced(row, || {
+                OpenPosition::from_node(row, timezone_map, eod_convention)
             })
-            .collect::<Result<Vec<T>>>()
+            .map(|r| self.open_positions.push(r)),
+            "Trade" if sections.contains(SectionSet::TRADES) => {
+                traced(row, || Trade::from_node(row, timezone_map)).map(|r| self.trades.push(r))
+            }
+            "TradeConfirm" if sections.contains(SectionSet::TRADE_CONFIRMS) => {
+                traced(row, || TradeConfirm::from_node(row, timezone_map))
+                    .map(|r| self.trade_confirms.push(r))
+            }
+            _ => Ok(()),
+        }
     }
 
-    fn parse_flex_statement(&self, node: &Node) -> Result<Statement> {
-        let account_infos = self.parse_section::<AccountInfo>(node, "AccountInformation")?;
-        if account_infos.len() > 1 {
+    /// Assembles the statement, requiring exactly one account information row.
+    ///
+    /// Trade confirmation reports have no account information section, so a statement
+    /// holding only trade confirmations takes its account id from `statement_account_id`,
+    /// the `FlexStatement`'s `accountId`.
+    fn into_statement(mut self, statement_account_id: Option<&str>) -> Result<Statement> {
+        if self.account_infos.is_empty()
+            && !self.trade_confirms.is_empty()
+            && let Some(account_id) = statement_account_id
+        {
+            self.account_infos.push(AccountInfo {
+                account_id: account_id.to_string(),
+            });
+        }
+        if self.account_infos.len() > 1 {
             return Err(anyhow::Error::msg(
                 "multiple account information sections found",
             ));
-        } else if account_infos.is_empty() {
+        } else if self.account_infos.is_empty() {
             return Err(anyhow::Error::msg("no account information sections found"));
         }
-        let account_info = account_infos[0].clone();
-
-        let cash_reports = self.parse_section(node, "CashReportCurrency")?;
-        let equity_summaries = self.parse_section(node, "EquitySummaryByReportDateInBase")?;
-        let fifo_performance_summaries =
-            self.parse_section(node, "FIFOPerformanceSummaryUnderlying")?;
-        let net_stock_positions = self.parse_section(node, "NetStockPosition")?;
-        let open_positions = self.parse_section(node, "OpenPosition")?;
-        let trades = self.parse_section_with_timezone(node, "Trade")?;
+        let account_info = self.account_infos.remove(0);
 
         Ok(Statement {
             account_info,
-            cash_reports,
-            equity_summaries,
-            fifo_performance_summaries,
-            net_stock_positions,
-            open_positions,
-            trades,
+            base_currency: self.base_currencies.into_iter().next(),
+            cash_reports: self.cash_reports,
+            conversion_rates: self.conversion_rates,
+            equity_summaries: self.equity_summaries,
+            fifo_performance_summaries: self.fifo_performance_summaries,
+            funds_lines: self.funds_lines,
+            net_stock_positions: self.net_stock_positions,
+            open_positions: self.open_positions,
+            trades: self.trades,
+            trade_confirms: self.trade_confirms,
         })
     }
+}
 
-    pub fn parse_flex_query_response(&self, flex_query_response: &str) -> Result<Vec<Statement>> {
-        let doc = Document::parse(flex_query_response)?;
-        doc.descendants()
-            .filter(|n| n.tag_name().name() == "FlexStatement")
-            .map(|n| self.parse_flex_statement(&n).map_err(anyhow::Error::msg))
-            .collect::<Result<Vec<Statement>>>()
+/// Runs a row's `from_node`, inside a `from_node` span when the `tracing` feature is enabled.
+fn traced<T>(row: &impl AttributeSource, from_node: impl FnOnce() -> Result<T>) -> Result<T> {
+    #[cfg(feature = "tracing")]
+    let _row_span = tracing::trace_span!(
+        "from_node",
+        tag = row.tag_name(),
+        account_id = row.attribute("accountId").unwrap_or_default()
+    )
+    .entered();
+    #[cfg(not(feature = "tracing"))]
+    let _ = row;
+
+    from_node()
+}
+
+/// Fails if any rows, given as `Tag (accountId)`, have an account id other than their
+/// statement's.
+fn check_account_id_offenders(statement_account_id: &str, offenders: &[String]) -> Result<()> {
+    if offenders.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::Error::msg(format!(
+            "account id mismatch with FlexStatement {}: {}",
+            statement_account_id,
+            offenders.join(", ")
+        )))
+    }
+}
+
+/// Returns the document's `FlexStatement` elements, checking each `FlexStatements` wrapper
+/// holds as many as its `count` attribute declares, so a truncated download is an error.
+fn flex_statement_nodes<'a>(doc: &'a Document<'a>) -> Result<Vec<Node<'a, 'a>>> {
+    for wrapper in doc
+        .descendants()
+        .filter(|n| n.tag_name().name() == "FlexStatements")
+    {
+        let wrapper = NodeWrapper { node: wrapper };
+        if let Some(declared) = wrapper.parse_attribute_opt::<usize>("count")? {
+            let found = wrapper
+                .node
+                .children()
+                .filter(|n| n.tag_name().name() == "FlexStatement")
+                .count();
+            if found != declared {
+                return Err(FlexParseError::StatementCountMismatch { declared, found }.into());
+            }
+        }
+    }
+
+    Ok(doc
+        .descendants()
+        .filter(|n| n.tag_name().name() == "FlexStatement")
+        .collect())
+}
+
+/// Parses a Flex query response with a default [`Parser`].
+///
+/// Use [`Parser`] directly to customize the timezone map or other parse options.
+///
+/// # Examples
+///
+/// ```
+/// let xml = r#"
+///     <FlexQueryResponse queryName="example-query" type="AF">
+///         <FlexStatements count="1">
+///             <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25">
+///                 <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks" />
+///             </FlexStatement>
+///         </FlexStatements>
+///     </FlexQueryResponse>
+/// "#;
+///
+/// let statements = ibkr_flex_statement::parse(xml).unwrap();
+/// assert_eq!(statements[0].account_info.account_id, "U1234567");
+/// ```
+pub fn parse(flex_query_response: &str) -> Result<Vec<Statement>> {
+    Parser::new().parse_flex_query_response(flex_query_response)
+}
+
+/// Reads a whole Flex query response. Only failing reads are reported as
+/// [`std::io::Error`]; input that is not UTF-8 is a [`std::string::FromUtf8Error`], as
+/// reading it again will not help.
+fn read_flex_query_response<R: std::io::Read>(mut reader: R) -> Result<String> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+/// Parses the response XML, rejecting the `FlexStatementResponse` error documents IBKR
+/// returns in place of a statement. A leading UTF-8 byte-order mark is skipped.
+fn parse_document(flex_query_response: &str) -> Result<Document<'_>> {
+    let flex_query_response = flex_query_response
+        .strip_prefix('\u{FEFF}')
+        .unwrap_or(flex_query_response);
+    let doc = Document::parse(flex_query_response)?;
+    let root = doc.root_element();
+    if root.tag_name().name() == "FlexStatementResponse" {
+        let child_text = |name: &str| {
+            root.children()
+                .find(|n| n.tag_name().name() == name)
+                .and_then(|n| n.text())
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        return Err(FlexParseError::ServiceError {
+            code: child_text("ErrorCode"),
+            message: child_text("ErrorMessage"),
+        }
+        .into());
     }
+    Ok(doc)
 }
 
 #[cfg(test)]
@@ -134,8 +621,10 @@ mod tests {
     use super::*;
     use anyhow::Result;
     use chrono_tz::Tz;
+    #[cfg(feature = "tracing")]
+    use std::fmt::Debug;
 
-    const FULL_STATEMENT_EXAMPLE: &str = r##"
+    pub(crate) const FULL_STATEMENT_EXAMPLE: &str = r##"
         <FlexQueryResponse queryName="example-query" type="AF">
             <FlexStatements count="1">
                 <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
@@ -188,7 +677,7 @@ mod tests {
 
     #[test]
     fn parsing_succeeds() -> Result<()> {
-        let statements = Parser::new()?.parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
         assert_eq!(statements.len(), 1);
         let result = &statements[0];
 
@@ -202,4 +691,548 @@ mod tests {
 
         Ok(())
     }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn decimal_amounts_round_trip_exactly() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        assert_eq!(
+            result.equity_summaries[0].cash_balance.to_string(),
+            "-1755658.753685009"
+        );
+        assert_eq!(
+            result.cash_reports[2].starting_cash.to_string(),
+            "-1755658.754517244"
+        );
+        assert_eq!(result.trades[0].commission.to_string(), "-1.000035");
+        assert_eq!(result.trades[0].price.to_string(), "606.57");
+        assert_eq!(
+            result.open_positions[0].fifo_pnl_unrealized.to_string(),
+            "-790.323674"
+        );
+        Ok(())
+    }
+
+    const CORRUPT_TRADE_EXAMPLE: &str = r##"
+        <FlexQueryResponse queryName="example-query" type="AF">
+            <FlexStatements count="1">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
+                    <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks,Options,Warrants,Forex,Futures,Crypto Currencies,Mutual Funds,Fully Paid Stock Loan" />
+                    <Trades>
+                        <Trade accountId="U1234567" currency="USD" symbol="ARGX" conid="276343981" listingExchange="NASDAQ" tradeID="7587063231" reportDate="2025-04-25" dateTime="2025-04-25;10:19:55 EDT" tradeDate="2025-04-25" transactionType="ExchTrade" exchange="BYX" quantity="1" tradePrice="606.57" tradeMoney="606.57" proceeds="-606.57" ibCommission="-1.000035" ibCommissionCurrency="USD" netCash="-607.570035" closePrice="614.76" openCloseIndicator="O" cost="607.570035" fifoPnlRealized="0" mtmPnl="8.19" origTradePrice="0" origTradeDate="" origTradeID="" origOrderID="0" origTransactionID="0" buySell="BUY" ibOrderID="4015030800" transactionID="32580112485" ibExecID="0000edae.680b59d1.01.01" orderTime="2025-04-25;10:19:55 EDT" openDateTime="" holdingPeriodDateTime="" whenRealized="" whenReopened="" orderType="LMT" accruedInt="0" assetCategory="STK" brokerageOrderID="002ce642.00014b44.680b0ed6.0001" orderReference="" isAPIOrder="N" initialInvestment="" />
+                        <Trade accountId="U1234567" currency="USD" symbol="BAD" conid="123" listingExchange="NYSE" tradeID="7587946870" reportDate="2025-04-25" dateTime="2025-04-25;11:20:00 EDT" tradeDate="2025-04-25" transactionType="ExchTrade" exchange="NYSE" quantity="not-a-number" tradePrice="30.85" tradeMoney="30850" proceeds="-30850" ibCommission="-5.035" ibCommissionCurrency="USD" netCash="-30855.035" closePrice="30.58" openCloseIndicator="O" cost="30855.035" fifoPnlRealized="0" mtmPnl="-270" origTradePrice="0" origTradeDate="" origTradeID="" origOrderID="0" origTransactionID="0" buySell="BUY" ibOrderID="4015577640" transactionID="32582764870" ibExecID="00012e0e.680b7717.01.00" orderTime="2025-04-25;11:20:00 EDT" openDateTime="" holdingPeriodDateTime="" whenRealized="" whenReopened="" orderType="LMT" accruedInt="0" assetCategory="STK" brokerageOrderID="002ce642.00014b44.680b0fbf.0000" orderReference="" isAPIOrder="N" initialInvestment="" />
+                        <Trade accountId="U1234567" currency="USD" symbol="GEO" conid="158655765" listingExchange="NYSE" tradeID="7587946875" reportDate="2025-04-25" dateTime="2025-04-25;11:24:28 EDT" tradeDate="2025-04-25" transactionType="ExchTrade" exchange="NYSE" quantity="1000" tradePrice="30.85" tradeMoney="30850" proceeds="-30850" ibCommission="-5.035" ibCommissionCurrency="USD" netCash="-30855.035" closePrice="30.58" openCloseIndicator="O" cost="30855.035" fifoPnlRealized="0" mtmPnl="-270" origTradePrice="0" origTradeDate="" origTradeID="" origOrderID="0" origTransactionID="0" buySell="BUY" ibOrderID="4015577648" transactionID="32582764875" ibExecID="00012e0e.680b7717.01.01" orderTime="2025-04-25;11:24:26 EDT" openDateTime="" holdingPeriodDateTime="" whenRealized="" whenReopened="" orderType="LMT" accruedInt="0" assetCategory="STK" brokerageOrderID="002ce642.00014b44.680b0fbf.0001" orderReference="" isAPIOrder="N" initialInvestment="" />
+                    </Trades>
+                </FlexStatement>
+            </FlexStatements>
+         </FlexQueryResponse>
+        "##;
+
+    #[test]
+    fn parse_file_reports_missing_file_as_io_error() {
+        let error = Parser::new()
+            .parse_file("/nonexistent/flex-statement.xml")
+            .unwrap_err();
+        let io_error = error.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(io_error.kind(), std::io::ErrorKind::NotFound);
+        assert!(error.downcast_ref::<roxmltree::Error>().is_none());
+    }
+
+    #[test]
+    fn parse_paths_keeps_order_and_per_file_errors() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("flex-parse-paths-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let good = dir.join("statement.xml");
+        std::fs::write(&good, FULL_STATEMENT_EXAMPLE)?;
+        let paths = vec![good.clone(), dir.join("missing.xml"), good];
+
+        let parser = Parser::new();
+        let results = parser.parse_paths(&paths);
+        std::fs::remove_dir_all(&dir)?;
+
+        assert_eq!(results.len(), 3);
+        let expected = parser.parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        assert_eq!(results[0].as_ref().unwrap(), &expected);
+        assert!(
+            results[1]
+                .as_ref()
+                .unwrap_err()
+                .downcast_ref::<std::io::Error>()
+                .is_some()
+        );
+        assert_eq!(results[2].as_ref().unwrap(), &expected);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_reader_reports_truncated_xml_as_parse_error() -> Result<()> {
+        let statements = Parser::new().parse_reader(FULL_STATEMENT_EXAMPLE.as_bytes())?;
+        assert_eq!(statements.len(), 1);
+
+        let truncated = &FULL_STATEMENT_EXAMPLE[..FULL_STATEMENT_EXAMPLE.len() / 2];
+        let error = Parser::new()
+            .parse_reader(truncated.as_bytes())
+            .unwrap_err();
+        #[cfg(not(feature = "fast"))]
+        assert!(error.downcast_ref::<roxmltree::Error>().is_some());
+        #[cfg(feature = "fast")]
+        assert!(error.to_string().starts_with("malformed XML"), "{}", error);
+        assert!(error.downcast_ref::<std::io::Error>().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_reader_reports_invalid_utf8_as_parse_error() {
+        let mut bytes = FULL_STATEMENT_EXAMPLE.as_bytes().to_vec();
+        bytes.extend_from_slice(&[0xff, 0xfe]);
+        let error = Parser::new().parse_reader(bytes.as_slice()).unwrap_err();
+        assert!(error.downcast_ref::<std::string::FromUtf8Error>().is_some());
+        assert!(error.downcast_ref::<std::io::Error>().is_none());
+    }
+
+    #[cfg(not(feature = "fast"))]
+    #[test]
+    fn malformed_xml_error_reports_position() {
+        let xml = "<FlexQueryResponse>\n    <FlexStatements count=\"1\">\n        <FlexStatement accountId=U1234567 />";
+        let error = Parser::new().parse_flex_query_response(xml).unwrap_err();
+        let xml_error = error.downcast_ref::<roxmltree::Error>().unwrap();
+        assert_eq!(xml_error.pos(), roxmltree::TextPos::new(3, 34));
+        assert!(error.to_string().contains("3:34"), "{}", error);
+    }
+
+    #[cfg(feature = "fast")]
+    #[test]
+    fn fast_malformed_xml_error_reports_position() {
+        let xml = "<FlexQueryResponse>\n    <FlexStatements count=\"1\">\n        <FlexStatement accountId=U1234567 />";
+        let error = Parser::new().parse_flex_query_response(xml).unwrap_err();
+        assert!(error.downcast_ref::<quick_xml::Error>().is_some());
+        assert!(
+            error.to_string().starts_with("malformed XML at byte"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn eod_convention_selects_close_hour() -> Result<()> {
+        let funds = r#"
+                    <StmtFunds>
+                        <StatementOfFundsLine accountId="U1234567" currency="USD" symbol="" conid="" date="2025-04-25" activityCode="" activityDescription="Starting Balance" amount="0" balance="-1856140.99825062" />
+                        <StatementOfFundsLine accountId="U1234567" currency="USD" symbol="GEO" conid="274115475" date="2025-04-25;10:02:15 EDT" activityCode="BUY" activityDescription="Buy 1,000 THE GEO GROUP INC " amount="-30850" balance="-1886990.99825062" />
+                    </StmtFunds>
+                </FlexStatement>"#;
+        let xml = FULL_STATEMENT_EXAMPLE.replacen("</FlexStatement>", funds, 1);
+        let after_hours = Parser::new().parse_flex_query_response(&xml)?;
+        let mut parser = Parser::new();
+        parser.eod_convention = EodConvention::RegularClose;
+        let regular_close = parser.parse_flex_query_response(&xml)?;
+
+        let four_hours_ms = 4 * 60 * 60 * 1000;
+        let (after_hours, regular_close) = (&after_hours[0], &regular_close[0]);
+        // 2025-04-25 16:00 EDT
+        assert_eq!(
+            regular_close.open_positions[0].timestamp_eod_ms,
+            1745611200000
+        );
+        assert_eq!(
+            after_hours.open_positions[0].timestamp_eod_ms - four_hours_ms,
+            regular_close.open_positions[0].timestamp_eod_ms
+        );
+        assert_eq!(
+            after_hours.equity_summaries[0].timestamp_eod_ms - four_hours_ms,
+            regular_close.equity_summaries[0].timestamp_eod_ms
+        );
+        assert_eq!(
+            after_hours.cash_reports[0].end_timestamp_ms - four_hours_ms,
+            regular_close.cash_reports[0].end_timestamp_ms
+        );
+        assert_eq!(
+            after_hours.funds_lines[0].timestamp_ms - four_hours_ms,
+            regular_close.funds_lines[0].timestamp_ms
+        );
+        // Funds lines with a time of day keep it.
+        assert_eq!(
+            after_hours.funds_lines[1].timestamp_ms,
+            regular_close.funds_lines[1].timestamp_ms
+        );
+        assert_eq!(after_hours.trades, regular_close.trades);
+        Ok(())
+    }
+
+    #[test]
+    fn strict_parsing_fails_on_corrupt_trade() -> Result<()> {
+        let error = Parser::new()
+            .parse_flex_query_response(CORRUPT_TRADE_EXAMPLE)
+            .unwrap_err();
+        assert!(error.to_string().starts_with("failed to parse Trade: "));
+        Ok(())
+    }
+
+    #[test]
+    fn lenient_parsing_keeps_valid_trades() -> Result<()> {
+        let (statements, errors) =
+            Parser::new().parse_flex_query_response_lenient(CORRUPT_TRADE_EXAMPLE)?;
+        assert_eq!(statements.len(), 1);
+        let result = &statements[0];
+
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.trades[0].ticker, "ARGX");
+        assert_eq!(result.trades[1].ticker, "GEO");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].tag_name, "Trade");
+        assert!(
+            errors[0]
+                .attributes
+                .contains(&("symbol".to_string(), "BAD".to_string()))
+        );
+        assert!(
+            errors[0]
+                .outer_xml
+                .starts_with(r#"<Trade accountId="U1234567""#)
+        );
+        assert!(errors[0].outer_xml.contains(r#"quantity="not-a-number""#));
+        assert!(errors[0].outer_xml.ends_with("/>"));
+        Ok(())
+    }
+
+    #[test]
+    fn strict_row_errors_downcast_to_section_error() -> Result<()> {
+        let parser = Parser::new();
+        for error in [
+            parser
+                .parse_selected(CORRUPT_TRADE_EXAMPLE, SectionSet::TRADES)
+                .unwrap_err(),
+            parser
+                .parse_flex_query_response(CORRUPT_TRADE_EXAMPLE)
+                .unwrap_err(),
+        ] {
+            let section_error = error.downcast_ref::<SectionError>().unwrap();
+            assert_eq!(section_error.tag_name, "Trade");
+            assert!(section_error.outer_xml.contains(r#"symbol="BAD""#));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_selected_only_populates_requested_sections() -> Result<()> {
+        let statements =
+            Parser::new().parse_selected(FULL_STATEMENT_EXAMPLE, SectionSet::TRADES)?;
+        assert_eq!(statements.len(), 1);
+        let result = &statements[0];
+
+        assert_eq!(result.account_info.account_id, "U1234567");
+        assert_eq!(result.trades.len(), 2);
+        assert!(result.cash_reports.is_empty());
+        assert!(result.equity_summaries.is_empty());
+        assert!(result.fifo_performance_summaries.is_empty());
+        assert!(result.net_stock_positions.is_empty());
+        assert!(result.open_positions.is_empty());
+        Ok(())
+    }
+
+    const MULTI_ACCOUNT_EXAMPLE: &str = r##"
+        <FlexQueryResponse queryName="example-query" type="AF">
+            <FlexStatements count="2">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
+                    <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks" />
+                    <NetStockPositionSummary>
+                        <NetStockPosition accountId="U1234567" currency="USD" assetCategory="STK" symbol="GRPN" conid="426480582" listingExchange="NASDAQ" netShares="3000" />
+                    </NetStockPositionSummary>
+                </FlexStatement>
+                <FlexStatement accountId="U7654321" fromDate="2025-04-25" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
+                    <AccountInformation accountId="U7654321" accountType="Individual" customerType="Individual" accountCapabilities="Cash" tradingPermissions="Stocks" />
+                    <NetStockPositionSummary>
+                        <NetStockPosition accountId="U7654321" currency="USD" assetCategory="STK" symbol="META" conid="107113386" listingExchange="NASDAQ" netShares="800" />
+                        <NetStockPosition accountId="U7654321" currency="USD" assetCategory="STK" symbol="NFLX" conid="15124833" listingExchange="NASDAQ" netShares="400" />
+                    </NetStockPositionSummary>
+                </FlexStatement>
+            </FlexStatements>
+        </FlexQueryResponse>
+        "##;
+
+    #[test]
+    fn parse_by_account_groups_statements() -> Result<()> {
+        let by_account = Parser::new().parse_by_account(MULTI_ACCOUNT_EXAMPLE)?;
+        assert_eq!(by_account.len(), 2);
+
+        assert_eq!(by_account["U1234567"].net_stock_positions.len(), 1);
+        assert_eq!(by_account["U7654321"].net_stock_positions.len(), 2);
+        assert_eq!(
+            by_account["U7654321"].account_info.account_id,
+            "U7654321".to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn strict_account_ids_rejects_mismatch() -> Result<()> {
+        let mismatched = FULL_STATEMENT_EXAMPLE.replacen(
+            r#"<AccountInformation accountId="U1234567""#,
+            r#"<AccountInformation accountId="U2418904""#,
+            1,
+        );
+
+        // The default parser stays lenient about the mismatch.
+        let statements = Parser::new().parse_flex_query_response(&mismatched)?;
+        assert_eq!(statements[0].account_info.account_id, "U2418904");
+
+        let mut parser = Parser::new();
+        parser.strict_account_ids = true;
+        let error = parser.parse_flex_query_response(&mismatched).unwrap_err();
+        assert!(error.to_string().contains("AccountInformation (U2418904)"));
+
+        // A consistent statement still parses in strict mode.
+        assert_eq!(
+            parser
+                .parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?
+                .len(),
+            1
+        );
+        Ok(())
+    }
+
+    const ACCOUNT_INFO_ONLY_EXAMPLE: &str = r##"
+        <FlexQueryResponse queryName="example-query" type="AF">
+            <FlexStatements count="1">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
+                    <AccountInformation accountId="U1234567" accountType="Individual" />
+                </FlexStatement>
+            </FlexStatements>
+        </FlexQueryResponse>
+        "##;
+
+    #[test]
+    fn account_info_only_statement_parses_with_empty_sections() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(ACCOUNT_INFO_ONLY_EXAMPLE)?;
+        assert_eq!(statements, vec![Statement::builder("U1234567").build()]);
+        Ok(())
+    }
+
+    #[cfg(feature = "streaming")]
+    #[test]
+    fn trade_iter_yields_statement_trades() -> Result<()> {
+        let parser = Parser::new();
+        let trades = parser
+            .trade_iter(FULL_STATEMENT_EXAMPLE.as_bytes())
+            .collect::<Result<Vec<Trade>>>()?;
+
+        assert_eq!(trades.len(), 2);
+        assert_eq!(
+            trades,
+            parser.parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?[0].trades
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "streaming")]
+    #[test]
+    fn trade_iter_yields_bad_rows_as_errors() {
+        let xml = CORRUPT_TRADE_EXAMPLE.replacen(r#" buySell="BUY""#, "", 1);
+        let parser = Parser::new();
+        let mut trades = parser.trade_iter(xml.as_bytes());
+
+        let error = trades.next().unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "missing attribute buySell");
+        assert!(trades.next().unwrap().is_err());
+        assert_eq!(trades.next().unwrap().unwrap().ticker, "GEO");
+        assert!(trades.next().is_none());
+    }
+
+    #[cfg(feature = "fast")]
+    #[test]
+    fn fast_backend_section_errors_match_dom_backend() -> Result<()> {
+        let parser = Parser::new();
+        let bad_row_end = r#"initialInvestment="" />
+                        <Trade accountId="U1234567" currency="USD" symbol="GEO""#;
+        let non_empty = CORRUPT_TRADE_EXAMPLE.replacen(
+            bad_row_end,
+            r#"initialInvestment=""></Trade>
+                        <Trade accountId="U1234567" currency="USD" symbol="GEO""#,
+            1,
+        );
+        assert_ne!(non_empty, CORRUPT_TRADE_EXAMPLE);
+
+        for xml in [CORRUPT_TRADE_EXAMPLE, non_empty.as_str()] {
+            let error = fast::parse_flex_query_response(&parser, xml).unwrap_err();
+            let fast_error = error.downcast_ref::<SectionError>().unwrap();
+            let (_, dom_errors) = parser.parse_flex_query_response_lenient(xml)?;
+            assert_eq!(dom_errors.len(), 1);
+
+            assert_eq!(fast_error.tag_name, dom_errors[0].tag_name);
+            assert_eq!(fast_error.attributes, dom_errors[0].attributes);
+            assert_eq!(fast_error.outer_xml, dom_errors[0].outer_xml);
+            assert_eq!(
+                fast_error.error.to_string(),
+                dom_errors[0].error.to_string()
+            );
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "fast")]
+    #[test]
+    fn fast_backend_matches_dom_backend() -> Result<()> {
+        let parser = Parser::new();
+        for xml in [
+            FULL_STATEMENT_EXAMPLE,
+            MULTI_ACCOUNT_EXAMPLE,
+            ACCOUNT_INFO_ONLY_EXAMPLE,
+        ] {
+            assert_eq!(
+                fast::parse_flex_query_response(&parser, xml)?,
+                parser.parse_flex_query_response_dom(xml)?
+            );
+        }
+
+        let error = fast::parse_flex_query_response(&parser, SERVICE_ERROR_EXAMPLE).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<FlexParseError>(),
+            Some(FlexParseError::ServiceError { .. })
+        ));
+        Ok(())
+    }
+
+    const SERVICE_ERROR_EXAMPLE: &str = r##"
+        <FlexStatementResponse timestamp="26 April, 2025 01:34 PM EDT">
+            <Status>Fail</Status>
+            <ErrorCode>1020</ErrorCode>
+            <ErrorMessage>Invalid request or unable to validate request.</ErrorMessage>
+        </FlexStatementResponse>
+        "##;
+
+    #[test]
+    fn statement_count_mismatch_is_an_error() {
+        let xml = FULL_STATEMENT_EXAMPLE.replacen(
+            r#"<FlexStatements count="1">"#,
+            r#"<FlexStatements count="2">"#,
+            1,
+        );
+        let error = Parser::new().parse_flex_query_response(&xml).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<FlexParseError>(),
+            Some(&FlexParseError::StatementCountMismatch {
+                declared: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn leading_byte_order_mark_is_skipped() -> Result<()> {
+        let xml = format!("\u{FEFF}{}", FULL_STATEMENT_EXAMPLE.trim_start());
+        let parser = Parser::new();
+        assert_eq!(
+            parser.parse_flex_query_response(&xml)?,
+            parser.parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn service_error_document_is_reported() -> Result<()> {
+        let error = Parser::new()
+            .parse_flex_query_response(SERVICE_ERROR_EXAMPLE)
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<FlexParseError>(),
+            Some(&FlexParseError::ServiceError {
+                code: "1020".to_string(),
+                message: "Invalid request or unable to validate request.".to_string(),
+            })
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_statement_parses_like_plain_text() -> Result<()> {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(FULL_STATEMENT_EXAMPLE.as_bytes())?;
+        let compressed = encoder.finish()?;
+
+        let parser = Parser::new();
+        assert_eq!(
+            parser.parse_gzip(compressed.as_slice())?,
+            parser.parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?
+        );
+        Ok(())
+    }
+
+    /// A span's name and its `(field, debug-formatted value)` pairs.
+    #[cfg(feature = "tracing")]
+    type RecordedSpan = (String, Vec<(String, String)>);
+
+    /// A layer recording the name and fields of every span created beneath it.
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct SpanRecorder {
+        spans: std::sync::Arc<std::sync::Mutex<Vec<RecordedSpan>>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanRecorder {
+        fn on_new_span(
+            &self,
+            span: &tracing::span::Attributes<'_>,
+            _: &tracing::span::Id,
+            _: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            struct Fields(Vec<(String, String)>);
+            impl tracing::field::Visit for Fields {
+                fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn Debug) {
+                    self.0
+                        .push((field.name().to_string(), format!("{:?}", value)));
+                }
+            }
+
+            let mut fields = Fields(Vec::new());
+            span.record(&mut fields);
+            self.spans
+                .lock()
+                .unwrap()
+                .push((span.metadata().name().to_string(), fields.0));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn parse_records_section_and_row_spans() -> Result<()> {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let recorder = SpanRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            Parser::new().parse_flex_query_response_dom(FULL_STATEMENT_EXAMPLE)
+        })?;
+
+        let spans = recorder.spans.lock().unwrap();
+        let field = |name: &str, value: &str| (name.to_string(), format!("{:?}", value));
+        assert!(spans.iter().any(|(name, fields)| {
+            name == "parse_flex_statement" && fields.contains(&field("account_id", "U1234567"))
+        }));
+        assert!(spans.iter().any(|(name, fields)| {
+            name == "parse_section"
+                && fields.contains(&field("section", "Trades"))
+                && fields.contains(&field("account_id", "U1234567"))
+        }));
+        let row_tags: Vec<&str> = spans
+            .iter()
+            .filter(|(name, _)| name == "from_node")
+            .filter_map(|(_, fields)| fields.iter().find(|(field, _)| field == "tag"))
+            .map(|(_, tag)| tag.as_str())
+            .collect();
+        assert_eq!(
+            row_tags.iter().filter(|tag| **tag == "\"Trade\"").count(),
+            2
+        );
+        assert!(!row_tags.contains(&"\"Trades\""));
+        Ok(())
+    }
 }
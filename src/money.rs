@@ -0,0 +1,29 @@
+//! Numeric type used for monetary and price fields.
+//!
+//! Amounts are `f64` by default. Enabling the `decimal` feature switches them to
+//! `rust_decimal::Decimal`, parsed directly from the statement's string attributes so
+//! values such as `-1755658.753685009` are represented exactly.
+
+#[cfg(not(feature = "decimal"))]
+pub type Money = f64;
+
+#[cfg(feature = "decimal")]
+pub type Money = rust_decimal::Decimal;
+
+/// Converts a non-monetary `f64`, such as a quantity, for arithmetic with [`Money`].
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn from_f64(value: f64) -> Money {
+    value
+}
+
+/// Converts a non-monetary `f64`, such as a quantity, for arithmetic with [`Money`].
+#[cfg(feature = "decimal")]
+pub(crate) fn from_f64(value: f64) -> Money {
+    use rust_decimal::prelude::FromPrimitive;
+    Money::from_f64(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+pub(crate) fn money(s: &str) -> Money {
+    s.parse().unwrap()
+}
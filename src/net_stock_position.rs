@@ -1,14 +1,15 @@
 use crate::asset_category::AssetCategory;
 use crate::currency::Currency;
-use crate::node_utils::NodeWrapper;
+use crate::node_utils::AttributeSource;
 use crate::statement_section::StatementSection;
 use anyhow::Result;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetStockPosition {
     pub account_id: String,
     pub asset_category: AssetCategory,
-    pub conid: u32,
+    pub conid: u64,
     pub currency: Currency,
     pub listing_exchange: String,
     pub net_shares: f64,
@@ -16,12 +17,12 @@ pub struct NetStockPosition {
 }
 
 impl StatementSection for NetStockPosition {
-    fn from_node(node: &NodeWrapper) -> Result<NetStockPosition> {
+    fn from_node(node: &impl AttributeSource) -> Result<NetStockPosition> {
         Ok(NetStockPosition {
             account_id: node.get_attribute("accountId")?,
-            asset_category: AssetCategory::try_from(node.node.attribute("assetCategory").unwrap())?,
+            asset_category: AssetCategory::try_from(node.get_attribute("assetCategory")?.as_str())?,
             conid: node.parse_attribute("conid")?,
-            currency: Currency::try_from(node.node.attribute("currency").unwrap())?,
+            currency: Currency::try_from(node.get_attribute("currency")?.as_str())?,
             net_shares: node.parse_attribute("netShares")?,
             listing_exchange: node.get_attribute("listingExchange")?,
             ticker: node.get_attribute("symbol")?,
@@ -64,7 +65,7 @@ mod tests {
 
     #[test]
     fn net_stock_positions_parse() -> Result<()> {
-        let statements = Parser::new()?.parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
         assert_eq!(statements.len(), 1);
         let result = &statements[0];
 
@@ -85,4 +86,29 @@ mod tests {
         );
         Ok(())
     }
+
+    const LARGE_CONID_EXAMPLE: &str = r##"
+        <FlexQueryResponse queryName="example-query" type="AF">
+            <FlexStatements count="1">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
+                    <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks,Options,Warrants,Forex,Futures,Crypto Currencies,Mutual Funds,Fully Paid Stock Loan" />
+                    <NetStockPositionSummary>
+                        <NetStockPosition accountId="U1234567" currency="USD" assetCategory="STK" symbol="BIGC" conid="5000000000" listingExchange="NASDAQ" netShares="10" />
+                    </NetStockPositionSummary>
+                </FlexStatement>
+            </FlexStatements>
+         </FlexQueryResponse>
+        "##;
+
+    #[test]
+    fn conid_larger_than_u32_parses() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(LARGE_CONID_EXAMPLE)?;
+        assert_eq!(statements.len(), 1);
+        let result = &statements[0];
+
+        assert_eq!(result.net_stock_positions.len(), 1);
+        assert_eq!(result.net_stock_positions[0].conid, 5_000_000_000);
+        assert!(result.net_stock_positions[0].conid > u32::MAX as u64);
+        Ok(())
+    }
 }
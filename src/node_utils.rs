@@ -3,17 +3,23 @@ use roxmltree::Node;
 use std::fmt::{Debug, Display};
 use std::str::FromStr;
 
-pub struct NodeWrapper<'a> {
-    pub node: Node<'a, 'a>,
-}
+/// The attributes of a statement row, read by the section parsers. Implemented for
+/// `roxmltree` nodes and, with the `fast` or `streaming` features, for `quick-xml` start tags.
+pub trait AttributeSource {
+    /// Returns the row's tag name, e.g. `Trade`.
+    fn tag_name(&self) -> &str;
 
-impl NodeWrapper<'_> {
-    pub fn get_attribute(&self, attribute_name: &str) -> Result<String> {
-        Ok(self.node.attribute(attribute_name).unwrap().to_string())
+    /// Returns the unescaped value of `attribute_name`, or `None` if the row lacks it.
+    fn attribute(&self, attribute_name: &str) -> Option<&str>;
+
+    fn get_attribute(&self, attribute_name: &str) -> Result<String> {
+        self.attribute(attribute_name)
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow::anyhow!("missing attribute {}", attribute_name))
     }
 
-    pub fn get_attribute_opt(&self, attribute_name: &str) -> Option<String> {
-        match self.node.attribute(attribute_name) {
+    fn get_attribute_opt(&self, attribute_name: &str) -> Option<String> {
+        match self.attribute(attribute_name) {
             Some(s) => {
                 if s.is_empty() {
                     None
@@ -25,32 +31,198 @@ impl NodeWrapper<'_> {
         }
     }
 
-    pub fn parse_attribute<T: FromStr>(&self, attribute_name: &str) -> Result<T>
+    fn parse_attribute<T: FromStr>(&self, attribute_name: &str) -> Result<T>
     where
         <T as FromStr>::Err: Send + Sync + Debug + Display,
         <T as FromStr>::Err: 'static,
     {
-        self.node
+        let value = self
             .attribute(attribute_name)
-            .unwrap()
-            .parse::<T>()
-            .map_err(anyhow::Error::msg)
+            .ok_or_else(|| anyhow::anyhow!("missing attribute {}", attribute_name))?
+            .trim();
+        if value.is_empty() {
+            return Err(anyhow::anyhow!("empty attribute {}", attribute_name));
+        }
+        value.parse::<T>().map_err(|e| {
+            anyhow::anyhow!("invalid attribute {}=\"{}\": {}", attribute_name, value, e)
+        })
     }
 
-    pub fn parse_attribute_opt<T: FromStr>(&self, attribute_name: &str) -> Result<Option<T>>
+    fn parse_attribute_opt<T: FromStr>(&self, attribute_name: &str) -> Result<Option<T>>
     where
         <T as FromStr>::Err: Send + Sync + Debug + Display,
         <T as FromStr>::Err: 'static,
     {
-        match self.node.attribute(attribute_name) {
+        match self.attribute(attribute_name) {
             Some(s) => {
+                let s = s.trim();
                 if s.is_empty() {
                     Ok(None)
                 } else {
-                    Ok(Some(s.parse::<T>().map_err(anyhow::Error::msg)?))
+                    Ok(Some(s.parse::<T>().map_err(|e| {
+                        anyhow::anyhow!("invalid attribute {}=\"{}\": {}", attribute_name, s, e)
+                    })?))
                 }
             }
             None => Ok(None),
         }
     }
 }
+
+pub struct NodeWrapper<'a> {
+    pub node: Node<'a, 'a>,
+}
+
+impl AttributeSource for NodeWrapper<'_> {
+    fn tag_name(&self) -> &str {
+        self.node.tag_name().name()
+    }
+
+    fn attribute(&self, attribute_name: &str) -> Option<&str> {
+        self.node.attribute(attribute_name)
+    }
+}
+
+/// A `quick-xml` start tag with its attributes unescaped, so rows can be parsed without
+/// building a DOM.
+#[cfg(any(feature = "fast", feature = "streaming"))]
+pub(crate) struct StartTag<'a> {
+    tag_name: &'a str,
+    attributes: Vec<(&'a str, std::borrow::Cow<'a, str>)>,
+}
+
+#[cfg(any(feature = "fast", feature = "streaming"))]
+impl<'a> StartTag<'a> {
+    pub(crate) fn new(start: &'a quick_xml::events::BytesStart<'_>) -> Result<Self> {
+        let attributes = start
+            .attributes()
+            .map(|attribute| {
+                let attribute = attribute.map_err(quick_xml::Error::from)?;
+                let value = attribute.unescape_value()?;
+                Ok((std::str::from_utf8(attribute.key.into_inner())?, value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(StartTag {
+            tag_name: std::str::from_utf8(start.local_name().into_inner())?,
+            attributes,
+        })
+    }
+
+    /// Wraps a failure to parse this row, as [`crate::error::SectionError::new`] does for a
+    /// DOM node. `outer_xml` is the element's source text, which the start tag alone does
+    /// not cover.
+    #[cfg(feature = "fast")]
+    pub(crate) fn section_error(
+        &self,
+        outer_xml: &str,
+        error: anyhow::Error,
+    ) -> crate::error::SectionError {
+        crate::error::SectionError {
+            tag_name: self.tag_name.to_string(),
+            attributes: self
+                .attributes
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            outer_xml: outer_xml.to_string(),
+            error,
+        }
+    }
+}
+
+#[cfg(any(feature = "fast", feature = "streaming"))]
+impl AttributeSource for StartTag<'_> {
+    fn tag_name(&self) -> &str {
+        self.tag_name
+    }
+
+    fn attribute(&self, attribute_name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| *name == attribute_name)
+            .map(|(_, value)| value.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::{Money, money};
+    use roxmltree::Document;
+
+    #[test]
+    fn parse_attribute_trims_whitespace() -> Result<()> {
+        let doc = Document::parse(r#"<Row markPrice=" 19.89 " quantity="1.5e2" empty="  " />"#)?;
+        let node = NodeWrapper {
+            node: doc.root_element(),
+        };
+
+        assert_eq!(node.parse_attribute::<Money>("markPrice")?, money("19.89"));
+        assert_eq!(
+            node.parse_attribute_opt::<Money>("markPrice")?,
+            Some(money("19.89"))
+        );
+        assert_eq!(node.parse_attribute::<f64>("quantity")?, 150.0);
+        assert_eq!(node.parse_attribute_opt::<f64>("empty")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_attribute_reports_missing_and_empty() -> Result<()> {
+        let doc = Document::parse(r#"<Row markPrice="" quantity="abc" />"#)?;
+        let node = NodeWrapper {
+            node: doc.root_element(),
+        };
+
+        let error = node.parse_attribute::<Money>("positionValue").unwrap_err();
+        assert_eq!(error.to_string(), "missing attribute positionValue");
+
+        let error = node.get_attribute("positionValue").unwrap_err();
+        assert_eq!(error.to_string(), "missing attribute positionValue");
+        assert_eq!(node.get_attribute("markPrice")?, "");
+
+        let error = node.parse_attribute::<Money>("markPrice").unwrap_err();
+        assert_eq!(error.to_string(), "empty attribute markPrice");
+
+        let error = node.parse_attribute::<f64>("quantity").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("invalid attribute quantity=\"abc\"")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_attribute_opt_names_invalid_attribute() -> Result<()> {
+        let doc = Document::parse(r#"<Row conid=" 12x " />"#)?;
+        let node = NodeWrapper {
+            node: doc.root_element(),
+        };
+
+        let error = node.parse_attribute_opt::<u64>("conid").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid attribute conid=\"12x\": invalid digit found in string"
+        );
+        assert_eq!(node.parse_attribute_opt::<u64>("missing")?, None);
+        Ok(())
+    }
+
+    #[cfg(any(feature = "fast", feature = "streaming"))]
+    #[test]
+    fn start_tag_reads_unescaped_attributes() -> Result<()> {
+        let mut reader = quick_xml::Reader::from_str(r#"<Trade symbol="A&amp;B" notes="" />"#);
+        let quick_xml::events::Event::Empty(start) = reader.read_event()? else {
+            panic!("expected an empty element");
+        };
+        let row = StartTag::new(&start)?;
+
+        assert_eq!(row.tag_name(), "Trade");
+        assert_eq!(row.attribute("symbol"), Some("A&B"));
+        assert_eq!(row.get_attribute_opt("notes"), None);
+        let error = row.get_attribute("conid").unwrap_err();
+        assert_eq!(error.to_string(), "missing attribute conid");
+        Ok(())
+    }
+}
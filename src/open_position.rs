@@ -1,28 +1,44 @@
 use super::currency::Currency;
-use super::time_utils;
+use super::time_utils::{self, EodConvention};
 use crate::asset_category::AssetCategory;
-use crate::node_utils::NodeWrapper;
-use crate::statement_section::StatementSection;
+use crate::money::{self, Money};
+use crate::node_utils::AttributeSource;
+use crate::statement_section::StatementSectionWithEod;
+use crate::trade::TradeSide;
 use anyhow::Result;
+use chrono_tz::Tz;
+use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PositionSide {
     Long,
     Short,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpenPosition {
     pub account_id: String,
+    /// Interest accrued since the last coupon, mainly for bonds; `None` for stocks.
+    pub accrued_interest: Option<Money>,
     pub asset_category: AssetCategory,
-    pub conid: u32,
-    pub cost_basis_price: f64,
-    pub fifo_pnl_unrealized: f64,
+    pub conid: u64,
+    pub cost_basis_price: Money,
+    pub fifo_pnl_unrealized: Money,
     pub currency: Currency,
+    /// When the holding period of the lot began, for long- vs short-term classification;
+    /// empty on summary rows.
+    pub holding_period_timestamp_ms: Option<i64>,
     pub listing_exchange: String,
-    pub mark_price: f64,
+    pub mark_price: Option<Money>,
+    /// When the lot was opened; empty on summary rows.
+    pub open_timestamp_ms: Option<i64>,
     pub open_quantity: f64,
-    pub position_value: f64,
+    /// Share of the account's NAV held in the position, in percent (`1.63` is 1.63%).
+    pub percent_of_nav: Option<f64>,
+    pub position_value: Option<Money>,
+    pub report_date: String,
     pub timestamp_eod_ms: i64,
     pub ticker: String,
     pub side: PositionSide,
@@ -40,24 +56,81 @@ impl<'a> TryFrom<&'a str> for PositionSide {
     }
 }
 
-impl StatementSection for OpenPosition {
-    fn from_node(node: &NodeWrapper) -> Result<OpenPosition> {
+/// The side of the position a trade adds to: buys add to longs and sells to shorts.
+impl From<TradeSide> for PositionSide {
+    fn from(side: TradeSide) -> Self {
+        match side {
+            TradeSide::Buy => Self::Long,
+            TradeSide::Sell => Self::Short,
+        }
+    }
+}
+
+impl StatementSectionWithEod for OpenPosition {
+    fn from_node(
+        node: &impl AttributeSource,
+        tz_map: &HashMap<String, Tz>,
+        eod_convention: EodConvention,
+    ) -> Result<OpenPosition> {
+        let report_date = node.get_attribute("reportDate")?;
+        let timestamp_eod_ms = time_utils::trading_eod_timestamp_ms(&report_date, eod_convention)?;
         Ok(OpenPosition {
             account_id: node.get_attribute("accountId")?,
-            asset_category: AssetCategory::try_from(node.node.attribute("assetCategory").unwrap())?,
+            accrued_interest: node.parse_attribute_opt("accruedInt")?,
+            asset_category: AssetCategory::try_from(node.get_attribute("assetCategory")?.as_str())?,
             conid: node.parse_attribute("conid")?,
             cost_basis_price: node.parse_attribute("costBasisPrice")?,
-            currency: Currency::try_from(node.node.attribute("currency").unwrap())?,
+            currency: Currency::try_from(node.get_attribute("currency")?.as_str())?,
             fifo_pnl_unrealized: node.parse_attribute("fifoPnlUnrealized")?,
+            holding_period_timestamp_ms: node
+                .get_attribute_opt("holdingPeriodDateTime")
+                .map(|s| time_utils::parse_ibkr_datetime_ms(tz_map, &s))
+                .transpose()?,
             listing_exchange: node.get_attribute("listingExchange")?,
-            mark_price: node.parse_attribute("markPrice")?,
+            mark_price: node.parse_attribute_opt("markPrice")?,
             open_quantity: node.parse_attribute("position")?,
-            position_value: node.parse_attribute("positionValue")?,
-            side: PositionSide::try_from(node.node.attribute("side").unwrap())?,
+            open_timestamp_ms: node
+                .get_attribute_opt("openDateTime")
+                .map(|s| time_utils::parse_ibkr_datetime_ms(tz_map, &s))
+                .transpose()?,
+            percent_of_nav: node.parse_attribute_opt("percentOfNAV")?,
+            position_value: node.parse_attribute_opt("positionValue")?,
+            report_date,
+            side: PositionSide::try_from(node.get_attribute("side")?.as_str())?,
             ticker: node.get_attribute("symbol")?,
-            timestamp_eod_ms: time_utils::trading_eod_after_hours_timestamp_ms(
-                node.node.attribute("reportDate").unwrap(),
-            )?,
+            timestamp_eod_ms,
+        })
+    }
+}
+
+impl OpenPosition {
+    /// Returns the quantity signed by side: positive for longs and negative for shorts.
+    ///
+    /// The sign is taken from `side` so the result does not depend on whether IBKR reports
+    /// the short quantity as negative.
+    pub fn signed_quantity(&self) -> f64 {
+        match self.side {
+            PositionSide::Long => self.open_quantity.abs(),
+            PositionSide::Short => -self.open_quantity.abs(),
+        }
+    }
+
+    /// Returns `position_value` signed by side, the position's economic exposure. Returns
+    /// `None` when the position value is missing.
+    pub fn signed_position_value(&self) -> Option<Money> {
+        self.position_value.map(|value| match self.side {
+            PositionSide::Long => value.abs(),
+            PositionSide::Short => -value.abs(),
+        })
+    }
+
+    /// Recomputes unrealized PnL from the mark price as `(mark - cost basis) * quantity`,
+    /// negated for short positions. Returns `None` when the mark price is missing.
+    pub fn computed_unrealized_pnl(&self) -> Option<Money> {
+        let quantity = money::from_f64(self.open_quantity.abs());
+        self.mark_price.map(|mark_price| match self.side {
+            PositionSide::Long => (mark_price - self.cost_basis_price) * quantity,
+            PositionSide::Short => (self.cost_basis_price - mark_price) * quantity,
         })
     }
 }
@@ -67,6 +140,7 @@ mod tests {
     use super::*;
     use crate::Parser;
     use crate::currency::Currency;
+    use crate::money::money;
     use anyhow::Result;
 
     const PARTIAL_STATEMENT_EXAMPLE: &str = r##"
@@ -109,7 +183,7 @@ mod tests {
 
     #[test]
     fn open_positions_parse() -> Result<()> {
-        let statements = Parser::new()?.parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
         assert_eq!(statements.len(), 1);
         let result = &statements[0];
 
@@ -120,15 +194,20 @@ mod tests {
             result.open_positions[6],
             OpenPosition {
                 account_id: "U1234567".to_string(),
+                accrued_interest: None,
                 asset_category: AssetCategory::Stock,
                 conid: 6478131,
-                cost_basis_price: 217.200032892,
-                fifo_pnl_unrealized: 4089.983554,
+                cost_basis_price: money("217.200032892"),
+                fifo_pnl_unrealized: money("4089.983554"),
                 currency: Currency::USD,
+                holding_period_timestamp_ms: None,
                 listing_exchange: "NASDAQ".to_string(),
-                mark_price: 225.38,
+                mark_price: Some(money("225.38")),
+                open_timestamp_ms: None,
                 open_quantity: 500.0,
-                position_value: 112690.0,
+                percent_of_nav: Some(3.08),
+                position_value: Some(money("112690.0")),
+                report_date: "2025-04-25".to_string(),
                 timestamp_eod_ms: result.open_positions[6].timestamp_eod_ms,
                 ticker: "TTWO".to_string(),
                 side: PositionSide::Long
@@ -136,4 +215,145 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn open_position_with_empty_mark_price_parses() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"markPrice="19.89" positionValue="59670""#,
+            r#"markPrice="" positionValue="""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let result = &statements[0];
+
+        assert_eq!(result.open_positions.len(), 7);
+        assert_eq!(result.open_positions[0].ticker, "GRPN");
+        assert_eq!(result.open_positions[0].mark_price, None);
+        assert_eq!(result.open_positions[0].position_value, None);
+        assert_eq!(result.open_positions[1].mark_price, Some(money("547.27")));
+        Ok(())
+    }
+
+    #[test]
+    fn computed_unrealized_pnl_matches_reported() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        for position in &statements[0].open_positions {
+            let computed = position.computed_unrealized_pnl().unwrap();
+            assert!(
+                (computed - position.fifo_pnl_unrealized).abs() < money("0.01"),
+                "{}: computed {} but reported {}",
+                position.ticker,
+                computed,
+                position.fifo_pnl_unrealized
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn computed_unrealized_pnl_flips_sign_for_shorts() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let mut position = statements[0].open_positions[0].clone();
+        position.side = PositionSide::Short;
+        position.open_quantity = -position.open_quantity;
+
+        let computed = position.computed_unrealized_pnl().unwrap();
+        assert!((computed - money("790.323675")).abs() < money("0.000001"));
+
+        position.mark_price = None;
+        assert_eq!(position.computed_unrealized_pnl(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn short_position_has_negative_exposure() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(r#"side="Long""#, r#"side="Short""#, 1);
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let short = &statements[0].open_positions[0];
+
+        assert_eq!(short.side, PositionSide::Short);
+        assert_eq!(short.open_quantity, 3000.0);
+        assert_eq!(short.signed_quantity(), -3000.0);
+        assert_eq!(short.signed_position_value(), Some(money("-59670")));
+        // The mark is below the cost basis, a gain for a short.
+        let computed = short.computed_unrealized_pnl().unwrap();
+        assert!((computed - money("790.323675")).abs() < money("0.000001"));
+
+        let long = &statements[0].open_positions[1];
+        assert_eq!(long.signed_quantity(), 800.0);
+        assert_eq!(long.signed_position_value(), Some(money("437816")));
+        Ok(())
+    }
+
+    #[test]
+    fn bond_accrued_interest_parses() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            "</OpenPositions>",
+            r#"<OpenPosition accountId="U1234567" currency="USD" assetCategory="BOND" symbol="T 4 02/15/34" conid="673277361" listingExchange="" reportDate="2025-04-25" position="10000" markPrice="97.5" positionValue="9750" openPrice="98.2" costBasisPrice="98.2" percentOfNAV="0.27" fifoPnlUnrealized="-70" side="Long" openDateTime="" holdingPeriodDateTime="" accruedInt="78.45" commodityType="" />
+                    </OpenPositions>"#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let result = &statements[0];
+
+        assert_eq!(result.open_positions.len(), 8);
+        let bond = &result.open_positions[7];
+        assert_eq!(
+            bond.asset_category,
+            AssetCategory::Other("BOND".to_string())
+        );
+        assert_eq!(bond.accrued_interest, Some(money("78.45")));
+        assert_eq!(result.open_positions[0].accrued_interest, None);
+        Ok(())
+    }
+
+    #[test]
+    fn lot_date_times_parse() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"openDateTime="" holdingPeriodDateTime="""#,
+            r#"openDateTime="2025-04-25;10:19:55 EDT" holdingPeriodDateTime="2025-04-25;10:19:55 EDT""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let result = &statements[0];
+
+        assert_eq!(
+            result.open_positions[0].open_timestamp_ms,
+            Some(1745590795000)
+        );
+        assert_eq!(
+            result.open_positions[0].holding_period_timestamp_ms,
+            Some(1745590795000)
+        );
+        assert_eq!(result.open_positions[1].open_timestamp_ms, None);
+        Ok(())
+    }
+
+    #[test]
+    fn lot_date_times_use_parser_timezone_map() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"openDateTime="""#,
+            r#"openDateTime="2025-04-25;15:19:55 BST""#,
+            1,
+        );
+        assert!(Parser::new().parse_flex_query_response(&xml).is_err());
+
+        let mut parser = Parser::new();
+        parser
+            .timezone_map
+            .insert("BST".to_string(), chrono_tz::Europe::London);
+        let statements = parser.parse_flex_query_response(&xml)?;
+        // 15:19:55 in London is 10:19:55 in New York.
+        assert_eq!(
+            statements[0].open_positions[0].open_timestamp_ms,
+            Some(1745590795000)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn position_side_from_trade_side() {
+        assert_eq!(PositionSide::from(TradeSide::Buy), PositionSide::Long);
+        assert_eq!(PositionSide::from(TradeSide::Sell), PositionSide::Short);
+    }
 }
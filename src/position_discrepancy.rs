@@ -0,0 +1,98 @@
+use crate::asset_category::AssetCategory;
+use crate::net_stock_position::NetStockPosition;
+use crate::open_position::OpenPosition;
+use std::collections::BTreeMap;
+
+/// A stock whose open position quantity disagrees with its net stock position.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PositionDiscrepancy {
+    pub conid: u64,
+    pub ticker: String,
+    /// Summed signed quantity of the stock's open positions, negative for shorts, or 0 if
+    /// there are none.
+    pub open_quantity: f64,
+    /// Summed `net_shares` of the stock's net stock positions, or 0 if there are none.
+    pub net_shares: f64,
+}
+
+/// Compares stock open positions against net stock positions by conid, returning the
+/// mismatches ordered by conid.
+pub(crate) fn cross_check_positions(
+    open_positions: &[OpenPosition],
+    net_stock_positions: &[NetStockPosition],
+) -> Vec<PositionDiscrepancy> {
+    let mut by_conid: BTreeMap<u64, PositionDiscrepancy> = BTreeMap::new();
+    for position in open_positions
+        .iter()
+        .filter(|p| p.asset_category == AssetCategory::Stock)
+    {
+        entry(&mut by_conid, position.conid, &position.ticker).open_quantity +=
+            position.signed_quantity();
+    }
+    for position in net_stock_positions {
+        entry(&mut by_conid, position.conid, &position.ticker).net_shares += position.net_shares;
+    }
+
+    by_conid
+        .into_values()
+        .filter(|d| (d.open_quantity - d.net_shares).abs() > f64::EPSILON)
+        .collect()
+}
+
+fn entry<'a>(
+    by_conid: &'a mut BTreeMap<u64, PositionDiscrepancy>,
+    conid: u64,
+    ticker: &str,
+) -> &'a mut PositionDiscrepancy {
+    by_conid
+        .entry(conid)
+        .or_insert_with(|| PositionDiscrepancy {
+            conid,
+            ticker: ticker.to_string(),
+            open_quantity: 0.0,
+            net_shares: 0.0,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+    use anyhow::Result;
+
+    #[test]
+    fn matching_positions_have_no_discrepancies() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        assert!(statements[0].cross_check_positions().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn mismatched_positions_are_reported() -> Result<()> {
+        let xml = FULL_STATEMENT_EXAMPLE.replacen(r#"netShares="3000""#, r#"netShares="2900""#, 1);
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+
+        assert_eq!(
+            statements[0].cross_check_positions(),
+            vec![PositionDiscrepancy {
+                conid: 426480582,
+                ticker: "GRPN".to_string(),
+                open_quantity: 3000.0,
+                net_shares: 2900.0,
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn short_positions_compare_by_signed_quantity() -> Result<()> {
+        let xml = FULL_STATEMENT_EXAMPLE
+            .replacen(r#"side="Long""#, r#"side="Short""#, 1)
+            .replacen(r#"netShares="3000""#, r#"netShares="-3000""#, 1);
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        assert!(statements[0].cross_check_positions().is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+//! Re-exports of the parser, statement, and section types.
+//!
+//! # Examples
+//!
+//! ```
+//! use ibkr_flex_statement::prelude::*;
+//!
+//! let xml = r#"
+//!     <FlexQueryResponse queryName="example-query" type="AF">
+//!         <FlexStatements count="1">
+//!             <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25">
+//!                 <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks" />
+//!                 <NetStockPositionSummary>
+//!                     <NetStockPosition accountId="U1234567" currency="USD" assetCategory="STK" symbol="TTWO" conid="6478131" listingExchange="NASDAQ" netShares="500" />
+//!                 </NetStockPositionSummary>
+//!             </FlexStatement>
+//!         </FlexStatements>
+//!     </FlexQueryResponse>
+//! "#;
+//!
+//! let statements: Vec<Statement> = Parser::new().parse_flex_query_response(xml).unwrap();
+//! let position: &NetStockPosition = &statements[0].net_stock_positions[0];
+//! assert_eq!(position.asset_category, AssetCategory::Stock);
+//! assert_eq!(position.currency, Currency::USD);
+//! ```
+
+pub use crate::Parser;
+pub use crate::account_info::{AccountInfo, BaseCurrency};
+pub use crate::aggregated_order::AggregatedOrder;
+pub use crate::approx_eq::ApproxEq;
+pub use crate::asset_category::AssetCategory;
+pub use crate::cash_report::CashReport;
+pub use crate::conversion_rate::ConversionRate;
+pub use crate::currency::Currency;
+pub use crate::equity_summary::EquitySummary;
+pub use crate::error::{FlexParseError, SectionError, ValidationError, ValidationWarning};
+pub use crate::exchange::Exchange;
+pub use crate::fifo_performance_summary::{FIFOPerformanceSummary, RealizedSummary};
+pub use crate::flex_response::{FlexResponse, ResponseType};
+pub use crate::ledger::LedgerEntry;
+pub use crate::money::Money;
+pub use crate::net_stock_position::NetStockPosition;
+pub use crate::open_position::{OpenPosition, PositionSide};
+pub use crate::position_discrepancy::PositionDiscrepancy;
+pub use crate::section_set::SectionSet;
+pub use crate::statement::{SectionCounts, Statement};
+pub use crate::statement_ref::{FlexDocument, StatementRef, TradeRef};
+pub use crate::stmt_funds::FundsLine;
+pub use crate::time_utils::EodConvention;
+pub use crate::trade::{
+    LevelOfDetail, OpenCloseIndicator, OrderType, Trade, TradeCode, TradeSide, TransactionType,
+};
+pub use crate::trade_confirm::TradeConfirm;
@@ -0,0 +1,58 @@
+use std::ops::{BitOr, BitOrAssign};
+
+/// A set of statement sections to populate when parsing.
+///
+/// Account information is always parsed, since every statement requires it.
+///
+/// # Examples
+///
+/// ```
+/// use ibkr_flex_statement::section_set::SectionSet;
+///
+/// let sections = SectionSet::TRADES | SectionSet::OPEN_POSITIONS;
+/// assert!(sections.contains(SectionSet::TRADES));
+/// assert!(!sections.contains(SectionSet::CASH_REPORTS));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SectionSet(u32);
+
+impl SectionSet {
+    pub const CASH_REPORTS: Self = Self(1 << 0);
+    pub const EQUITY_SUMMARIES: Self = Self(1 << 1);
+    pub const FIFO_PERFORMANCE_SUMMARIES: Self = Self(1 << 2);
+    pub const NET_STOCK_POSITIONS: Self = Self(1 << 3);
+    pub const OPEN_POSITIONS: Self = Self(1 << 4);
+    pub const TRADES: Self = Self(1 << 5);
+    pub const FUNDS_LINES: Self = Self(1 << 6);
+    pub const CONVERSION_RATES: Self = Self(1 << 7);
+    pub const TRADE_CONFIRMS: Self = Self(1 << 8);
+    pub const ALL: Self = Self((1 << 9) - 1);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for SectionSet {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for SectionSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for SectionSet {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
@@ -0,0 +1,1509 @@
+use crate::account_info::{AccountInfo, BaseCurrency};
+use crate::aggregated_order::{self, AggregatedOrder};
+#[cfg(feature = "beancount")]
+use crate::beancount::{self, BeancountOptions};
+use crate::cash_report::CashReport;
+use crate::conversion_rate::ConversionRate;
+use crate::currency::Currency;
+#[cfg(feature = "polars")]
+use crate::dataframe;
+use crate::equity_summary::EquitySummary;
+use crate::error::ValidationWarning;
+use crate::fifo_performance_summary::{FIFOPerformanceSummary, RealizedSummary};
+use crate::ledger::{self, LedgerEntry};
+use crate::money::{self, Money};
+use crate::net_stock_position::NetStockPosition;
+use crate::open_position::OpenPosition;
+use crate::position_discrepancy::{self, PositionDiscrepancy};
+use crate::stmt_funds::FundsLine;
+use crate::time_utils;
+use crate::trade::{LevelOfDetail, Trade, TransactionType};
+use crate::trade_confirm::TradeConfirm;
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Statement {
+    pub account_info: AccountInfo,
+    pub base_currency: Option<BaseCurrency>,
+    pub cash_reports: Vec<CashReport>,
+    pub conversion_rates: Vec<ConversionRate>,
+    pub equity_summaries: Vec<EquitySummary>,
+    pub fifo_performance_summaries: Vec<FIFOPerformanceSummary>,
+    pub funds_lines: Vec<FundsLine>,
+    pub net_stock_positions: Vec<NetStockPosition>,
+    pub open_positions: Vec<OpenPosition>,
+    pub trades: Vec<Trade>,
+    pub trade_confirms: Vec<TradeConfirm>,
+}
+
+/// The number of rows in each section of a [`Statement`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SectionCounts {
+    pub cash_reports: usize,
+    pub conversion_rates: usize,
+    pub equity_summaries: usize,
+    pub fifo_performance_summaries: usize,
+    pub funds_lines: usize,
+    pub net_stock_positions: usize,
+    pub open_positions: usize,
+    pub trades: usize,
+    pub trade_confirms: usize,
+}
+
+impl Statement {
+    /// Starts building a statement for `account_id` from explicit section rows, e.g. to
+    /// construct fixtures in tests without going through XML.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ibkr_flex_statement::Statement;
+    ///
+    /// let statement = Statement::builder("U1234567").build();
+    /// assert_eq!(statement.account_id(), "U1234567");
+    /// assert!(statement.trades.is_empty());
+    /// ```
+    pub fn builder(account_id: &str) -> StatementBuilder {
+        StatementBuilder {
+            statement: Statement {
+                account_info: AccountInfo {
+                    account_id: account_id.to_string(),
+                },
+                base_currency: None,
+                cash_reports: Vec::new(),
+                conversion_rates: Vec::new(),
+                equity_summaries: Vec::new(),
+                fifo_performance_summaries: Vec::new(),
+                funds_lines: Vec::new(),
+                net_stock_positions: Vec::new(),
+                open_positions: Vec::new(),
+                trades: Vec::new(),
+                trade_confirms: Vec::new(),
+            },
+        }
+    }
+
+    /// Returns the IBKR account id the statement belongs to.
+    pub fn account_id(&self) -> &str {
+        &self.account_info.account_id
+    }
+
+    /// Combines several statements for the same account into one chronological statement.
+    ///
+    /// Trades, trade confirmations, cash reports, conversion rates, equity summaries, FIFO
+    /// performance summaries, funds lines, and open positions are concatenated, sorted by
+    /// their timestamps, and identical rows are dropped. Net stock positions carry no
+    /// timestamp, so they are taken from the last statement given; the base currency is taken
+    /// from the last statement that has one. Returns an error if no statements are given or
+    /// their account ids differ.
+    pub fn merge(statements: Vec<Statement>) -> Result<Statement> {
+        let Some(first) = statements.first() else {
+            return Err(anyhow::Error::msg("no statements to merge"));
+        };
+        let account_info = first.account_info.clone();
+        if let Some(other) = statements
+            .iter()
+            .find(|s| s.account_id() != account_info.account_id)
+        {
+            return Err(anyhow::Error::msg(format!(
+                "cannot merge statements for different accounts {} and {}",
+                account_info.account_id,
+                other.account_id()
+            )));
+        }
+
+        let mut base_currency = None;
+        let mut cash_reports = Vec::new();
+        let mut conversion_rates = Vec::new();
+        let mut equity_summaries = Vec::new();
+        let mut fifo_performance_summaries = Vec::new();
+        let mut funds_lines = Vec::new();
+        let mut net_stock_positions = Vec::new();
+        let mut open_positions = Vec::new();
+        let mut trades = Vec::new();
+        let mut trade_confirms = Vec::new();
+        for statement in statements {
+            base_currency = statement.base_currency.or(base_currency);
+            cash_reports.extend(statement.cash_reports);
+            conversion_rates.extend(statement.conversion_rates);
+            equity_summaries.extend(statement.equity_summaries);
+            fifo_performance_summaries.extend(statement.fifo_performance_summaries);
+            funds_lines.extend(statement.funds_lines);
+            net_stock_positions = statement.net_stock_positions;
+            open_positions.extend(statement.open_positions);
+            trades.extend(statement.trades);
+            trade_confirms.extend(statement.trade_confirms);
+        }
+
+        Ok(Statement {
+            account_info,
+            base_currency,
+            cash_reports: sorted_unique(cash_reports, |r| r.start_timestamp_ms),
+            conversion_rates: sorted_unique(conversion_rates, |r| r.timestamp_eod_ms),
+            equity_summaries: sorted_unique(equity_summaries, |s| s.timestamp_eod_ms),
+            fifo_performance_summaries: sorted_unique(fifo_performance_summaries, |s| {
+                s.timestamp_eod_ms
+            }),
+            funds_lines: sorted_unique(funds_lines, |l| l.timestamp_ms),
+            net_stock_positions,
+            open_positions: sorted_unique(open_positions, |p| p.timestamp_eod_ms),
+            trades: sorted_unique(trades, |t| t.execution_timestamp_ms),
+            trade_confirms: sorted_unique(trade_confirms, |t| t.execution_timestamp_ms),
+        })
+    }
+
+    /// Returns `true` when every section is empty, e.g. for a statement holding only
+    /// account information.
+    pub fn is_empty(&self) -> bool {
+        self.section_counts() == SectionCounts::default()
+    }
+
+    /// Returns the number of rows in each section.
+    pub fn section_counts(&self) -> SectionCounts {
+        SectionCounts {
+            cash_reports: self.cash_reports.len(),
+            conversion_rates: self.conversion_rates.len(),
+            equity_summaries: self.equity_summaries.len(),
+            fifo_performance_summaries: self.fifo_performance_summaries.len(),
+            funds_lines: self.funds_lines.len(),
+            net_stock_positions: self.net_stock_positions.len(),
+            open_positions: self.open_positions.len(),
+            trades: self.trades.len(),
+            trade_confirms: self.trade_confirms.len(),
+        }
+    }
+
+    /// Returns a one-paragraph description of the statement for logging or debugging: its
+    /// account, the New York dates it covers, the row count of each section, and the total
+    /// unrealized PnL per currency.
+    pub fn to_summary_string(&self) -> String {
+        let dates = self
+            .report_date_range()
+            .and_then(|(start, end)| {
+                let start = time_utils::new_york_date_from_timestamp_ms(start)?;
+                let end = time_utils::new_york_date_from_timestamp_ms(end)?;
+                Some(format!("{} to {}", start, end))
+            })
+            .unwrap_or_else(|| "no dated rows".to_string());
+
+        let counts = self.section_counts();
+        let sections = [
+            (counts.cash_reports, "cash reports"),
+            (counts.conversion_rates, "conversion rates"),
+            (counts.equity_summaries, "equity summaries"),
+            (
+                counts.fifo_performance_summaries,
+                "FIFO performance summaries",
+            ),
+            (counts.funds_lines, "funds lines"),
+            (counts.net_stock_positions, "net stock positions"),
+            (counts.open_positions, "open positions"),
+            (counts.trades, "trades"),
+            (counts.trade_confirms, "trade confirms"),
+        ]
+        .iter()
+        .map(|(count, name)| format!("{} {}", count, name))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+        let mut totals = self.total_unrealized_pnl().into_iter().collect::<Vec<_>>();
+        totals.sort_by_key(|(currency, _)| currency.to_string());
+        let unrealized = totals
+            .iter()
+            .map(|(currency, pnl)| format!("{:.2} {}", pnl, currency))
+            .collect::<Vec<_>>();
+        let unrealized = if unrealized.is_empty() {
+            "none".to_string()
+        } else {
+            unrealized.join(", ")
+        };
+
+        format!(
+            "Account {}, {}: {}. Unrealized PnL: {}.",
+            self.account_id(),
+            dates,
+            sections,
+            unrealized
+        )
+    }
+
+    /// Renders the non-cancelled execution-level trades as Beancount transactions dated by
+    /// their trade date, with postings for the instrument and cash in the trade's currency
+    /// and for the commission in its own currency.
+    #[cfg(feature = "beancount")]
+    pub fn to_beancount(&self, options: &BeancountOptions) -> String {
+        beancount::render_trades(
+            self.executions().into_iter().filter(|t| !t.is_cancel),
+            options,
+        )
+    }
+
+    /// Returns the trades as a `DataFrame` with one row per trade. Amounts are `Float64`
+    /// columns and the execution time is a millisecond `Datetime` in UTC.
+    #[cfg(feature = "polars")]
+    pub fn trades_dataframe(&self) -> Result<polars::frame::DataFrame> {
+        dataframe::trades_dataframe(&self.trades)
+    }
+
+    /// Returns the open positions as a `DataFrame` with one row per position. Amounts are
+    /// `Float64` columns and the report date is a millisecond `Datetime` in UTC.
+    #[cfg(feature = "polars")]
+    pub fn open_positions_dataframe(&self) -> Result<polars::frame::DataFrame> {
+        dataframe::open_positions_dataframe(&self.open_positions)
+    }
+
+    /// Serializes the statement to JSON. Timestamps are written as epoch milliseconds.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserializes a statement previously written by [`Statement::to_json`].
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Statement> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Converts an amount into the account's base currency using the most recent conversion
+    /// rate for `currency`. Returns `None` when the statement has no rate for it.
+    pub fn to_base_currency(&self, amount: Money, currency: Currency) -> Option<Money> {
+        if currency == Currency::BASE
+            || self
+                .conversion_rates
+                .iter()
+                .any(|r| r.to_currency == currency)
+        {
+            return Some(amount);
+        }
+        self.conversion_rates
+            .iter()
+            .filter(|r| r.from_currency == currency)
+            .max_by_key(|r| r.timestamp_eod_ms)
+            .map(|r| amount * r.rate)
+    }
+
+    /// Sums `fifo_pnl_unrealized` across all open positions, keyed by each position's
+    /// currency. Amounts in different currencies are never added together.
+    pub fn total_unrealized_pnl(&self) -> HashMap<Currency, Money> {
+        let mut totals = HashMap::new();
+        for position in &self.open_positions {
+            *totals.entry(position.currency.clone()).or_default() += position.fifo_pnl_unrealized;
+        }
+        totals
+    }
+
+    /// Sums `total_realized_pnl` across the per-instrument FIFO performance summaries.
+    ///
+    /// FIFO performance summaries are reported in the account's base currency, so a single
+    /// amount is returned. The aggregate row IBKR emits with an empty symbol is skipped so
+    /// that it is not counted twice.
+    pub fn total_realized_pnl(&self) -> Money {
+        let mut total = Money::default();
+        for summary in self
+            .fifo_performance_summaries
+            .iter()
+            .filter(|s| s.ticker.is_some())
+        {
+            total += summary.total_realized_pnl;
+        }
+        total
+    }
+
+    /// Groups realized short- and long-term gains and losses by symbol, summing rows that
+    /// share a symbol. The empty-symbol aggregate row is skipped; its totals equal the sum
+    /// over all symbols.
+    pub fn realized_summary_by_symbol(&self) -> HashMap<String, RealizedSummary> {
+        let mut by_symbol: HashMap<String, RealizedSummary> = HashMap::new();
+        for summary in &self.fifo_performance_summaries {
+            if let Some(ticker) = &summary.ticker {
+                by_symbol.entry(ticker.clone()).or_default().add(summary);
+            }
+        }
+        by_symbol
+    }
+
+    /// Returns the open position for the given contract id, if any.
+    pub fn open_position_by_conid(&self, conid: u64) -> Option<&OpenPosition> {
+        self.open_positions.iter().find(|p| p.conid == conid)
+    }
+
+    /// Returns all trades for the given symbol, in statement order.
+    pub fn trades_for_symbol(&self, symbol: &str) -> Vec<&Trade> {
+        self.trades.iter().filter(|t| t.ticker == symbol).collect()
+    }
+
+    /// Returns the trades executed in `[start_ms, end_ms)`, in statement order.
+    pub fn trades_in_range(&self, start_ms: i64, end_ms: i64) -> Vec<&Trade> {
+        self.trades
+            .iter()
+            .filter(|t| (start_ms..end_ms).contains(&t.execution_timestamp_ms))
+            .collect()
+    }
+
+    /// Sums the traded notional, `|trade_money|`, of the execution-level trades keyed by
+    /// currency. Cancellation rows are skipped, as in [`Statement::aggregate_orders`].
+    pub fn gross_turnover(&self) -> HashMap<Currency, Money> {
+        let mut totals = HashMap::new();
+        for trade in self.executions().into_iter().filter(|t| !t.is_cancel) {
+            *totals.entry(trade.currency.clone()).or_default() += trade.trade_money.abs();
+        }
+        totals
+    }
+
+    /// Sums the traded quantity, `|quantity|`, of the execution-level trades, skipping
+    /// cancellation rows.
+    pub fn share_volume(&self) -> f64 {
+        self.executions()
+            .into_iter()
+            .filter(|t| !t.is_cancel)
+            .map(|t| t.quantity.abs())
+            .sum()
+    }
+
+    /// Sums the commissions of the execution-level trades keyed by ticker and commission
+    /// currency, so commissions charged in different currencies are never added together.
+    /// IBKR reports commissions as negative amounts; cancellation rows are included so a
+    /// refunded commission offsets the original charge.
+    pub fn commissions_by_symbol(&self) -> HashMap<(String, Currency), Money> {
+        let mut totals = HashMap::new();
+        for trade in self.executions() {
+            *totals
+                .entry((trade.ticker.clone(), trade.commission_currency.clone()))
+                .or_default() += trade.commission;
+        }
+        totals
+    }
+
+    /// Returns the trades ordered by execution time.
+    ///
+    /// Rows are ordered by their timestamp alone; the sort is stable, so rows sharing a
+    /// timestamp keep statement order. The section types do not implement `Ord` because
+    /// their float fields have no total order.
+    pub fn sorted_trades(&self) -> Vec<&Trade> {
+        sorted_by_timestamp(&self.trades, |t| t.execution_timestamp_ms)
+    }
+
+    /// Groups the trades by their `tradeDate`, each day's trades ordered by execution time.
+    ///
+    /// The trade date is the day IBKR books the trade on, which can differ from the New
+    /// York date of the execution, e.g. for fills after midnight New York time on Asian
+    /// venues.
+    pub fn trades_by_date(&self) -> BTreeMap<NaiveDate, Vec<&Trade>> {
+        let mut by_date: BTreeMap<NaiveDate, Vec<&Trade>> = BTreeMap::new();
+        for trade in self.sorted_trades() {
+            by_date.entry(trade.trade_date).or_default().push(trade);
+        }
+        by_date
+    }
+
+    /// Returns the cash reports ordered by the start of their reporting period.
+    pub fn sorted_cash_reports(&self) -> Vec<&CashReport> {
+        sorted_by_timestamp(&self.cash_reports, |r| r.start_timestamp_ms)
+    }
+
+    /// Returns the per-currency cash reports, skipping the `BASE_SUMMARY` row.
+    ///
+    /// The `BASE_SUMMARY` row aggregates every currency converted to the base currency, so
+    /// summing it together with the per-currency rows double-counts.
+    pub fn cash_reports_excluding_base(&self) -> Vec<&CashReport> {
+        self.cash_reports
+            .iter()
+            .filter(|r| r.currency != Currency::BASE)
+            .collect()
+    }
+
+    /// Returns the cash report for `currency`, if any. Use [`Currency::BASE`] for the
+    /// `BASE_SUMMARY` row.
+    ///
+    /// A statement covers one period, so each currency is expected once; should it repeat,
+    /// the first row is returned.
+    pub fn cash_report_for_currency(&self, currency: &Currency) -> Option<&CashReport> {
+        self.cash_reports.iter().find(|r| &r.currency == currency)
+    }
+
+    /// Maps each currency, including [`Currency::BASE`], to its cash report. Should a
+    /// currency repeat, the first row wins, matching [`Statement::cash_report_for_currency`].
+    pub fn cash_reports_by_currency(&self) -> HashMap<Currency, &CashReport> {
+        let mut by_currency = HashMap::new();
+        for report in &self.cash_reports {
+            by_currency.entry(report.currency.clone()).or_insert(report);
+        }
+        by_currency
+    }
+
+    /// Sums net external cash flow, deposits plus withdrawals, across the cash reports keyed
+    /// by currency. IBKR reports withdrawals as negative amounts, so the result is the net
+    /// amount deposited.
+    ///
+    /// The `BASE_SUMMARY` row is skipped to avoid double counting; it already holds the
+    /// total across currencies in the base currency.
+    pub fn net_deposits_by_currency(&self) -> HashMap<Currency, Money> {
+        let mut totals = HashMap::new();
+        for report in self.cash_reports_excluding_base() {
+            *totals.entry(report.currency.clone()).or_default() +=
+                report.deposits + report.withdrawals;
+        }
+        totals
+    }
+
+    /// Sums commissions, other fees, and broker fees across the cash reports keyed by
+    /// currency. IBKR reports fees as negative amounts, so the totals are negative costs.
+    ///
+    /// The `BASE_SUMMARY` row is skipped to avoid double counting; its fee fields hold the
+    /// total across currencies in the base currency.
+    pub fn total_fees(&self) -> HashMap<Currency, Money> {
+        let mut totals = HashMap::new();
+        for report in self.cash_reports_excluding_base() {
+            *totals.entry(report.currency.clone()).or_default() +=
+                report.commissions + report.other_fees + report.broker_fees;
+        }
+        totals
+    }
+
+    /// Estimates net liquidation value per currency as the summed signed position value of
+    /// the open positions, negative for shorts, plus the ending cash of the cash reports,
+    /// keyed by currency.
+    ///
+    /// Amounts are not converted: each currency holds only the positions and cash
+    /// denominated in it, so convert with the statement's conversion rates before summing
+    /// across currencies. The `BASE_SUMMARY` cash row is skipped, positions without a
+    /// `position_value` are ignored, and accruals are not included, so the result can
+    /// differ from the equity summary's `total` by the accrued interest and dividends.
+    pub fn net_liquidation_value(&self) -> HashMap<Currency, Money> {
+        let mut totals: HashMap<Currency, Money> = HashMap::new();
+        for position in &self.open_positions {
+            if let Some(position_value) = position.signed_position_value() {
+                *totals.entry(position.currency.clone()).or_default() += position_value;
+            }
+        }
+        for report in self.cash_reports_excluding_base() {
+            *totals.entry(report.currency.clone()).or_default() += report.ending_cash;
+        }
+        totals
+    }
+
+    /// Sums `percent_of_nav` across the open positions, skipping positions without it.
+    ///
+    /// The result is the share of NAV held in positions, in percent. With the cash weight
+    /// excluded it should be near 100 for a fully invested account, lower when holding cash
+    /// and higher on margin; short positions count negatively. Each row is rounded to two
+    /// decimals, so allow a small tolerance. A large deviation from the positions' share of
+    /// the equity summary points to a parsing or data problem.
+    pub fn sum_percent_of_nav(&self) -> f64 {
+        self.open_positions
+            .iter()
+            .filter_map(|p| p.percent_of_nav)
+            .sum()
+    }
+
+    /// Returns the equity summaries ordered by report date.
+    pub fn sorted_equity_summaries(&self) -> Vec<&EquitySummary> {
+        sorted_by_timestamp(&self.equity_summaries, |s| s.timestamp_eod_ms)
+    }
+
+    /// Returns the FIFO performance summaries ordered by report date.
+    pub fn sorted_fifo_performance_summaries(&self) -> Vec<&FIFOPerformanceSummary> {
+        sorted_by_timestamp(&self.fifo_performance_summaries, |s| s.timestamp_eod_ms)
+    }
+
+    /// Returns the distinct symbols referenced by positions, trades, FIFO performance
+    /// summaries, and funds lines. Rows without a symbol, such as the FIFO total row, are
+    /// skipped.
+    pub fn unique_symbols(&self) -> BTreeSet<String> {
+        self.instruments()
+            .filter_map(|(symbol, _)| symbol)
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Returns the distinct conids referenced by positions, trades, FIFO performance
+    /// summaries, and funds lines.
+    pub fn unique_conids(&self) -> BTreeSet<u64> {
+        self.instruments().filter_map(|(_, conid)| conid).collect()
+    }
+
+    /// Maps each conid to its symbol, using every row that carries both.
+    ///
+    /// When rows disagree on the symbol for a conid (e.g. after a ticker change), the first
+    /// one wins, checking open positions, net stock positions, trades, FIFO performance
+    /// summaries, and funds lines in that order, so the current position's symbol is kept.
+    pub fn conid_symbol_map(&self) -> HashMap<u64, String> {
+        let mut map = HashMap::new();
+        for (symbol, conid) in self.instruments() {
+            if let (Some(symbol), Some(conid)) = (symbol, conid) {
+                map.entry(conid).or_insert_with(|| symbol.to_string());
+            }
+        }
+        map
+    }
+
+    /// Yields the `(symbol, conid)` pair of every instrument row, with empty symbols as `None`.
+    fn instruments(&self) -> impl Iterator<Item = (Option<&str>, Option<u64>)> {
+        let open_positions = self
+            .open_positions
+            .iter()
+            .map(|p| (Some(p.ticker.as_str()), Some(p.conid)));
+        let net_stock_positions = self
+            .net_stock_positions
+            .iter()
+            .map(|p| (Some(p.ticker.as_str()), Some(p.conid)));
+        let trades = self
+            .trades
+            .iter()
+            .map(|t| (Some(t.ticker.as_str()), Some(t.conid)));
+        let fifo_performance_summaries = self
+            .fifo_performance_summaries
+            .iter()
+            .map(|s| (s.ticker.as_deref(), s.conid));
+        let funds_lines = self
+            .funds_lines
+            .iter()
+            .map(|l| (l.ticker.as_deref(), l.conid));
+
+        open_positions
+            .chain(net_stock_positions)
+            .chain(trades)
+            .chain(fifo_performance_summaries)
+            .chain(funds_lines)
+            .map(|(symbol, conid)| (symbol.filter(|s| !s.is_empty()), conid))
+    }
+
+    /// Returns the earliest and latest timestamps referenced by any dated section, or `None`
+    /// if the statement has no dated rows. Cash reports contribute both ends of their period.
+    pub fn report_date_range(&self) -> Option<(i64, i64)> {
+        let timestamps = self
+            .cash_reports
+            .iter()
+            .flat_map(|r| [r.start_timestamp_ms, r.end_timestamp_ms])
+            .chain(self.conversion_rates.iter().map(|r| r.timestamp_eod_ms))
+            .chain(self.equity_summaries.iter().map(|s| s.timestamp_eod_ms))
+            .chain(
+                self.fifo_performance_summaries
+                    .iter()
+                    .map(|s| s.timestamp_eod_ms),
+            )
+            .chain(self.funds_lines.iter().map(|l| l.timestamp_ms))
+            .chain(self.open_positions.iter().map(|p| p.timestamp_eod_ms))
+            .chain(self.trades.iter().map(|t| t.execution_timestamp_ms))
+            .chain(self.trade_confirms.iter().map(|t| t.execution_timestamp_ms));
+
+        timestamps.fold(None, |range, ts| match range {
+            None => Some((ts, ts)),
+            Some((min, max)) => Some((min.min(ts), max.max(ts))),
+        })
+    }
+
+    /// Returns the equity summary reported for the given (New York) date, if any.
+    pub fn equity_summary_for_date(&self, date: NaiveDate) -> Option<&EquitySummary> {
+        self.equity_summaries
+            .iter()
+            .find(|s| time_utils::new_york_date_from_timestamp_ms(s.timestamp_eod_ms) == Some(date))
+    }
+
+    /// Returns the most recent equity summary, if any.
+    pub fn latest_equity_summary(&self) -> Option<&EquitySummary> {
+        self.equity_summaries
+            .iter()
+            .max_by_key(|s| s.timestamp_eod_ms)
+    }
+
+    /// Returns the day-over-day change in equity `total`, keyed by the later summary's
+    /// timestamp.
+    pub fn equity_total_deltas(&self) -> Vec<(i64, Money)> {
+        let mut summaries: Vec<&EquitySummary> = self.equity_summaries.iter().collect();
+        summaries.sort_by_key(|s| s.timestamp_eod_ms);
+        summaries
+            .windows(2)
+            .map(|pair| (pair[1].timestamp_eod_ms, pair[1].total - pair[0].total))
+            .collect()
+    }
+
+    /// Removes trades whose [`Trade::dedup_key`] matches an earlier trade, keeping the first.
+    ///
+    /// Unlike [`Statement::merge`], which drops only identical rows, this also drops rows that
+    /// IBKR re-reported with different values, e.g. a later `closePrice`.
+    pub fn dedup_trades(&mut self) {
+        let mut seen = HashSet::new();
+        self.trades
+            .retain(|t| seen.insert(t.dedup_key().to_string()));
+    }
+
+    /// Returns the [`Trade::dedup_key`]s that appear on more than one trade, each once, in
+    /// order of first appearance. An empty result means no row was reported twice, as
+    /// happens when a download is appended to itself.
+    pub fn duplicate_trade_ids(&self) -> Vec<String> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for trade in &self.trades {
+            *counts.entry(trade.dedup_key()).or_default() += 1;
+        }
+        let mut reported = HashSet::new();
+        self.trades
+            .iter()
+            .map(Trade::dedup_key)
+            .filter(|key| counts[key] > 1 && reported.insert(*key))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Returns the execution-level trade rows.
+    pub fn executions(&self) -> Vec<&Trade> {
+        self.trades
+            .iter()
+            .filter(|t| t.level_of_detail == LevelOfDetail::Execution)
+            .collect()
+    }
+
+    /// Returns the order-level trade rows.
+    pub fn orders(&self) -> Vec<&Trade> {
+        self.trades
+            .iter()
+            .filter(|t| t.level_of_detail == LevelOfDetail::Order)
+            .collect()
+    }
+
+    /// Returns the trades executed on an exchange (`ExchTrade`), excluding book trades and
+    /// cancellations.
+    pub fn exchange_trades(&self) -> Vec<&Trade> {
+        self.trades
+            .iter()
+            .filter(|t| t.transaction_type == TransactionType::ExchTrade)
+            .collect()
+    }
+
+    /// Returns the trades booked by IBKR rather than executed on an exchange (`BookTrade`).
+    pub fn book_trades(&self) -> Vec<&Trade> {
+        self.trades
+            .iter()
+            .filter(|t| t.transaction_type == TransactionType::BookTrade)
+            .collect()
+    }
+
+    /// Rolls execution-level trades up into one [`AggregatedOrder`] per `ibOrderID`.
+    ///
+    /// Cancelled executions are skipped. Orders appear in the order of their first execution.
+    pub fn aggregate_orders(&self) -> Vec<AggregatedOrder> {
+        aggregated_order::aggregate_orders(self.executions().into_iter().filter(|t| !t.is_cancel))
+    }
+
+    /// Compares stock `open_quantity` in the open positions against `net_shares` in the net
+    /// stock positions by conid and returns the conids where they disagree.
+    pub fn cross_check_positions(&self) -> Vec<PositionDiscrepancy> {
+        position_discrepancy::cross_check_positions(&self.open_positions, &self.net_stock_positions)
+    }
+
+    /// Combines the cash movements of the statement into one ledger ordered by timestamp.
+    ///
+    /// Each execution-level trade contributes its [`Trade::net_proceeds`], commission
+    /// included, at its execution time; a commission charged in another currency is entered
+    /// separately in that currency instead. Each per-currency cash report contributes its
+    /// nonzero dividends, broker interest, fees, deposits, and withdrawals at the end of its
+    /// period, since the cash report only has period totals. Commissions are not taken from
+    /// the cash reports, as they are already part of the trades. The `BASE_SUMMARY` row is
+    /// skipped to avoid double counting.
+    pub fn cash_ledger(&self) -> Vec<LedgerEntry> {
+        ledger::cash_ledger(
+            self.executions().into_iter(),
+            self.cash_reports_excluding_base().into_iter(),
+        )
+    }
+
+    /// Sums the signed quantity of the execution-level trades per conid, reconstructing the
+    /// position change over the statement period. A cancellation reverses the trade it
+    /// cancels.
+    ///
+    /// This matches `net_stock_positions` only for instruments held flat at the start of the
+    /// period, so it serves as a cross-check rather than a replacement.
+    pub fn position_from_trades(&self) -> HashMap<u64, f64> {
+        let mut positions = HashMap::new();
+        for trade in self.executions() {
+            let quantity = trade.signed_quantity();
+            *positions.entry(trade.conid).or_default() +=
+                if trade.is_cancel { -quantity } else { quantity };
+        }
+        positions
+    }
+
+    /// Runs every cross-section integrity check and reports the problems found; it never
+    /// fails. The checks are:
+    ///
+    /// * every row's `account_id` matches [`Statement::account_id`],
+    /// * [`Statement::cross_check_positions`],
+    /// * [`EquitySummary::validate`] and [`FIFOPerformanceSummary::validate`], to within
+    ///   one cent,
+    /// * [`Statement::duplicate_trade_ids`].
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let account_ids = [
+            (
+                "CashReportCurrency",
+                self.cash_reports.iter().map(|r| &r.account_id).collect(),
+            ),
+            (
+                "EquitySummaryByReportDateInBase",
+                self.equity_summaries
+                    .iter()
+                    .map(|s| &s.account_id)
+                    .collect(),
+            ),
+            (
+                "FIFOPerformanceSummaryUnderlying",
+                self.fifo_performance_summaries
+                    .iter()
+                    .map(|s| &s.account_id)
+                    .collect(),
+            ),
+            (
+                "StatementOfFundsLine",
+                self.funds_lines.iter().map(|l| &l.account_id).collect(),
+            ),
+            (
+                "NetStockPosition",
+                self.net_stock_positions
+                    .iter()
+                    .map(|p| &p.account_id)
+                    .collect(),
+            ),
+            (
+                "OpenPosition",
+                self.open_positions.iter().map(|p| &p.account_id).collect(),
+            ),
+            ("Trade", self.trades.iter().map(|t| &t.account_id).collect()),
+            (
+                "TradeConfirm",
+                self.trade_confirms.iter().map(|t| &t.account_id).collect(),
+            ),
+        ];
+        let mut warnings: Vec<ValidationWarning> = account_ids
+            .into_iter()
+            .flat_map(|(section, ids): (&str, Vec<&String>)| {
+                ids.into_iter()
+                    .filter(|id| id.as_str() != self.account_id())
+                    .map(move |id| ValidationWarning::AccountIdMismatch {
+                        section: section.to_string(),
+                        account_id: id.clone(),
+                    })
+            })
+            .collect();
+
+        warnings.extend(
+            self.cross_check_positions()
+                .into_iter()
+                .map(ValidationWarning::PositionMismatch),
+        );
+
+        let epsilon = money::from_f64(0.01);
+        warnings.extend(
+            self.equity_summaries
+                .iter()
+                .filter_map(|s| s.validate(epsilon).err())
+                .chain(
+                    self.fifo_performance_summaries
+                        .iter()
+                        .filter_map(|s| s.validate(epsilon).err()),
+                )
+                .map(ValidationWarning::Inconsistent),
+        );
+
+        warnings.extend(
+            self.duplicate_trade_ids()
+                .into_iter()
+                .map(ValidationWarning::DuplicateTradeId),
+        );
+        warnings
+    }
+
+    /// Returns the net stock position for the given symbol, if any.
+    pub fn net_position_for_symbol(&self, symbol: &str) -> Option<&NetStockPosition> {
+        self.net_stock_positions.iter().find(|p| p.ticker == symbol)
+    }
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_summary_string())
+    }
+}
+
+/// Parses a Flex query response holding a single `FlexStatement` with a default
+/// [`crate::Parser`]. Fails if the response contains no statement or more than one; use
+/// [`crate::Parser::parse_flex_query_response`] for those.
+///
+/// ```
+/// use ibkr_flex_statement::Statement;
+///
+/// let xml = r#"
+///     <FlexQueryResponse queryName="example-query" type="AF">
+///         <FlexStatements count="1">
+///             <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25">
+///                 <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks" />
+///             </FlexStatement>
+///         </FlexStatements>
+///     </FlexQueryResponse>
+/// "#;
+///
+/// let statement = xml.parse::<Statement>().unwrap();
+/// assert_eq!(statement.account_id(), "U1234567");
+/// ```
+impl FromStr for Statement {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut statements = crate::Parser::new().parse_flex_query_response(s)?;
+        if statements.len() != 1 {
+            return Err(anyhow::anyhow!(
+                "expected exactly one FlexStatement, found {}",
+                statements.len()
+            ));
+        }
+        Ok(statements.remove(0))
+    }
+}
+
+/// Assembles a [`Statement`] section by section. Created by [`Statement::builder`].
+pub struct StatementBuilder {
+    statement: Statement,
+}
+
+impl StatementBuilder {
+    pub fn base_currency(mut self, base_currency: BaseCurrency) -> Self {
+        self.statement.base_currency = Some(base_currency);
+        self
+    }
+
+    pub fn cash_reports(mut self, cash_reports: Vec<CashReport>) -> Self {
+        self.statement.cash_reports = cash_reports;
+        self
+    }
+
+    pub fn conversion_rates(mut self, conversion_rates: Vec<ConversionRate>) -> Self {
+        self.statement.conversion_rates = conversion_rates;
+        self
+    }
+
+    pub fn equity_summaries(mut self, equity_summaries: Vec<EquitySummary>) -> Self {
+        self.statement.equity_summaries = equity_summaries;
+        self
+    }
+
+    pub fn fifo_performance_summaries(
+        mut self,
+        fifo_performance_summaries: Vec<FIFOPerformanceSummary>,
+    ) -> Self {
+        self.statement.fifo_performance_summaries = fifo_performance_summaries;
+        self
+    }
+
+    pub fn funds_lines(mut self, funds_lines: Vec<FundsLine>) -> Self {
+        self.statement.funds_lines = funds_lines;
+        self
+    }
+
+    pub fn net_stock_positions(mut self, net_stock_positions: Vec<NetStockPosition>) -> Self {
+        self.statement.net_stock_positions = net_stock_positions;
+        self
+    }
+
+    pub fn open_positions(mut self, open_positions: Vec<OpenPosition>) -> Self {
+        self.statement.open_positions = open_positions;
+        self
+    }
+
+    pub fn trades(mut self, trades: Vec<Trade>) -> Self {
+        self.statement.trades = trades;
+        self
+    }
+
+    pub fn trade_confirms(mut self, trade_confirms: Vec<TradeConfirm>) -> Self {
+        self.statement.trade_confirms = trade_confirms;
+        self
+    }
+
+    pub fn build(self) -> Statement {
+        self.statement
+    }
+}
+
+fn sorted_by_timestamp<T>(rows: &[T], timestamp: impl Fn(&T) -> i64) -> Vec<&T> {
+    let mut sorted: Vec<&T> = rows.iter().collect();
+    sorted.sort_by_key(|r| timestamp(r));
+    sorted
+}
+
+/// Stable-sorts rows by timestamp and drops rows identical to an earlier row with the same
+/// timestamp.
+fn sorted_unique<T: PartialEq>(mut rows: Vec<T>, timestamp: impl Fn(&T) -> i64) -> Vec<T> {
+    rows.sort_by_key(&timestamp);
+    let mut unique: Vec<T> = Vec::with_capacity(rows.len());
+    for row in rows {
+        let ts = timestamp(&row);
+        let duplicate = unique
+            .iter()
+            .rev()
+            .take_while(|u| timestamp(u) == ts)
+            .any(|u| *u == row);
+        if !duplicate {
+            unique.push(row);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::asset_category::AssetCategory;
+    use crate::money::money;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+    use anyhow::Result;
+
+    #[test]
+    fn total_unrealized_pnl_sums_open_positions() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let totals = result.total_unrealized_pnl();
+        assert_eq!(totals.len(), 1);
+
+        // -790.323674 + 4199.716517 + 18081.807156 + 24855.882465
+        //   + 2837.648645 + 32948.519862 + 4089.983554
+        let usd_total = totals[&Currency::USD];
+        assert!((usd_total - money("86223.234525")).abs() < money("0.000001"));
+        Ok(())
+    }
+
+    #[test]
+    fn total_realized_pnl_skips_aggregate_row() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        // Only the TTWO row counts; the empty-symbol aggregate row is skipped.
+        assert_eq!(result.total_realized_pnl(), money("0"));
+        Ok(())
+    }
+
+    #[test]
+    fn open_position_by_conid_finds_position() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let position = result.open_position_by_conid(107113386).unwrap();
+        assert_eq!(position.ticker, "META");
+        assert!(result.open_position_by_conid(1).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn cloned_statement_equals_original() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let statement = statements[0].clone();
+        assert_eq!(statement, statements[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn unique_symbols_and_conids_span_sections() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let symbols: Vec<String> = result.unique_symbols().into_iter().collect();
+        assert_eq!(
+            symbols,
+            vec![
+                "ARGX", "GEO", "GRPN", "META", "NFLX", "PLTR", "TQQQ", "TSLA", "TTWO"
+            ]
+        );
+
+        let conids = result.unique_conids();
+        assert_eq!(conids.len(), 9);
+        assert!(conids.contains(&6478131));
+        assert!(conids.contains(&158655765));
+        Ok(())
+    }
+
+    #[test]
+    fn conid_symbol_map_spans_sections() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let map = result.conid_symbol_map();
+        assert_eq!(map.len(), 9);
+        assert_eq!(map[&6478131], "TTWO");
+        assert_eq!(map[&158655765], "GEO");
+        assert_eq!(map[&426480582], "GRPN");
+        Ok(())
+    }
+
+    #[test]
+    fn dedup_trades_removes_shared_trades() -> Result<()> {
+        let mut statement = Parser::new()
+            .parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?
+            .remove(0);
+        let mut overlapping = statement.clone();
+        overlapping.trades[1].close_price = money("30.60");
+        overlapping
+            .trades
+            .push(Parser::new().parse_flex_query_response(PRIOR_DAY_EXAMPLE)?[0].trades[0].clone());
+
+        statement.trades.extend(overlapping.trades);
+        statement.dedup_trades();
+
+        let tickers: Vec<&str> = statement.trades.iter().map(|t| t.ticker.as_str()).collect();
+        assert_eq!(tickers, vec!["ARGX", "GEO", "TTWO"]);
+        assert_eq!(statement.trades[1].close_price, money("30.58"));
+        Ok(())
+    }
+
+    #[test]
+    fn builder_assembles_minimal_statement() {
+        let statement = Statement::builder("U1234567")
+            .net_stock_positions(vec![NetStockPosition {
+                account_id: "U1234567".to_string(),
+                asset_category: AssetCategory::Stock,
+                conid: 6478131,
+                currency: Currency::USD,
+                listing_exchange: "NASDAQ".to_string(),
+                net_shares: 500.0,
+                ticker: "TTWO".to_string(),
+            }])
+            .build();
+
+        assert_eq!(statement.account_id(), "U1234567");
+        assert_eq!(
+            statement
+                .net_position_for_symbol("TTWO")
+                .unwrap()
+                .net_shares,
+            500.0
+        );
+        assert!(statement.trades.is_empty());
+    }
+
+    #[test]
+    fn report_date_range_spans_sections() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        // 2025-04-24 and 2025-04-25 20:00 EDT, from the equity summaries.
+        assert_eq!(
+            result.report_date_range(),
+            Some((1745539200000, 1745625600000))
+        );
+        assert_eq!(
+            Statement::builder("U1234567").build().report_date_range(),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sorted_trades_orders_by_execution_time() -> Result<()> {
+        let mut statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &mut statements[0];
+        result.trades.reverse();
+        assert_eq!(result.trades[0].ticker, "GEO");
+
+        let sorted = result.sorted_trades();
+        assert_eq!(sorted.len(), 2);
+        assert_eq!(sorted[0].ticker, "ARGX");
+        assert_eq!(sorted[1].ticker, "GEO");
+        assert!(sorted[0].execution_timestamp_ms < sorted[1].execution_timestamp_ms);
+        Ok(())
+    }
+
+    #[test]
+    fn trades_for_symbol_filters_trades() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let trades = result.trades_for_symbol("GEO");
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].conid, 158655765);
+        assert!(result.trades_for_symbol("META").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn net_position_for_symbol_finds_position() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let position = result.net_position_for_symbol("TQQQ").unwrap();
+        assert_eq!(position.net_shares, 34100.0);
+        assert!(result.net_position_for_symbol("ARGX").is_none());
+        Ok(())
+    }
+
+    const PRIOR_DAY_EXAMPLE: &str = r##"
+        <FlexQueryResponse queryName="example-query" type="AF">
+            <FlexStatements count="1">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-24" toDate="2025-04-24" period="LastBusinessDay" whenGenerated="2025-04-25;13:34:28 EDT">
+                    <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks,Options,Warrants,Forex,Futures,Crypto Currencies,Mutual Funds,Fully Paid Stock Loan" />
+                    <EquitySummaryInBase>
+                        <EquitySummaryByReportDateInBase accountId="U1234567" currency="USD" cash="-1755658.753685009" cashLong="0.000832235" cashShort="-1755658.754517244" commodities="0" commoditiesLong="0" commoditiesShort="0" dividendAccruals="0" dividendAccrualsLong="0" dividendAccrualsShort="0" interestAccruals="1292.18" interestAccrualsLong="1591.34" interestAccrualsShort="-299.16" stock="3441241" stockLong="3441241" stockShort="0" funds="0" fundsLong="0" fundsShort="0" brokerInterestAccrualsComponent="186.32" brokerInterestAccrualsComponentLong="485.48" brokerInterestAccrualsComponentShort="-299.16" brokerFeesAccrualsComponent="0" brokerFeesAccrualsComponentLong="0" brokerFeesAccrualsComponentShort="0" total="1686874.426314991" totalLong="3442832.340832235" totalShort="-1755957.914517244" reportDate="2025-04-24" />
+                    </EquitySummaryInBase>
+                    <CashReport>
+                        <CashReportCurrency accountId="U1234567" currency="USD" fromDate="2025-04-24" toDate="2025-04-24" netTradesSales="0" netTradesPurchases="0" startingCash="-1755658.754517244" commissions="0" dividends="0" otherFees="0" otherIncome="0" endingCash="-1755658.754517244" endingSettledCash="-1755658.754517244" brokerInterest="0" brokerFees="0" deposits="0" withdrawals="0" />
+                    </CashReport>
+                    <Trades>
+                        <Trade accountId="U1234567" currency="USD" symbol="TTWO" conid="6478131" listingExchange="NASDAQ" tradeID="7580000001" reportDate="2025-04-24" dateTime="2025-04-24;15:01:02 EDT" tradeDate="2025-04-24" transactionType="ExchTrade" exchange="NASDAQ" quantity="500" tradePrice="217.2" tradeMoney="108600" proceeds="-108600" ibCommission="-2.5" ibCommissionCurrency="USD" netCash="-108602.5" closePrice="218.1" openCloseIndicator="O" cost="108602.5" fifoPnlRealized="0" mtmPnl="450" origTradePrice="0" origTradeDate="" origTradeID="" origOrderID="0" origTransactionID="0" buySell="BUY" ibOrderID="4010000001" transactionID="32570000001" ibExecID="0000edae.680a0001.01.01" orderTime="2025-04-24;15:01:00 EDT" openDateTime="" holdingPeriodDateTime="" whenRealized="" whenReopened="" orderType="MKT" accruedInt="0" assetCategory="STK" brokerageOrderID="002ce642.00014b44.680a0001.0001" orderReference="" isAPIOrder="N" initialInvestment="" />
+                    </Trades>
+                </FlexStatement>
+            </FlexStatements>
+         </FlexQueryResponse>
+        "##;
+
+    #[test]
+    fn merge_combines_statements_chronologically() -> Result<()> {
+        let parser = Parser::new();
+        let mut statements = parser.parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        statements.extend(parser.parse_flex_query_response(PRIOR_DAY_EXAMPLE)?);
+
+        let merged = Statement::merge(statements)?;
+        assert_eq!(merged.account_info.account_id, "U1234567");
+
+        // The prior-day trade sorts ahead of the two later trades.
+        assert_eq!(merged.trades.len(), 3);
+        assert_eq!(merged.trades[0].ticker, "TTWO");
+        assert_eq!(merged.trades[1].ticker, "ARGX");
+        assert_eq!(merged.trades[2].ticker, "GEO");
+
+        // The 2025-04-24 equity summary appears in both statements but is kept once.
+        assert_eq!(merged.equity_summaries.len(), 2);
+        assert!(
+            merged.equity_summaries[0].timestamp_eod_ms
+                < merged.equity_summaries[1].timestamp_eod_ms
+        );
+
+        assert_eq!(merged.cash_reports.len(), 4);
+        assert!(
+            merged.cash_reports[0].start_timestamp_ms < merged.cash_reports[1].start_timestamp_ms
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn merge_rejects_different_accounts() -> Result<()> {
+        let parser = Parser::new();
+        let mut statements = parser.parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let other =
+            parser.parse_flex_query_response(&PRIOR_DAY_EXAMPLE.replace("U1234567", "U7654321"))?;
+        statements.extend(other);
+
+        assert!(Statement::merge(statements).is_err());
+        assert!(Statement::merge(Vec::new()).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let json = result.to_json()?;
+        assert!(json.contains(&result.trades[0].execution_timestamp_ms.to_string()));
+        assert_eq!(&Statement::from_json(&json)?, result);
+        Ok(())
+    }
+
+    #[test]
+    fn equity_summary_for_date_finds_summary() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let date = NaiveDate::from_ymd_opt(2025, 4, 24).unwrap();
+        let summary = result.equity_summary_for_date(date).unwrap();
+        assert_eq!(summary.stock_balance, money("3441241"));
+
+        let date = NaiveDate::from_ymd_opt(2025, 4, 25).unwrap();
+        let summary = result.equity_summary_for_date(date).unwrap();
+        assert_eq!(summary.stock_balance, money("3664457"));
+
+        let date = NaiveDate::from_ymd_opt(2025, 4, 26).unwrap();
+        assert!(result.equity_summary_for_date(date).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn latest_equity_summary_is_most_recent() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let latest = result.latest_equity_summary().unwrap();
+        assert_eq!(latest.stock_balance, money("3664457"));
+        Ok(())
+    }
+
+    #[test]
+    fn equity_total_deltas_are_day_over_day() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let deltas = result.equity_total_deltas();
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].0, result.equity_summaries[1].timestamp_eod_ms);
+        assert!((deltas[0].1 - money("122492.995434388")).abs() < money("0.000001"));
+        Ok(())
+    }
+
+    #[test]
+    fn position_from_trades_matches_net_positions() -> Result<()> {
+        // GEO was bought from flat during the period, so its net position equals the trades.
+        let xml = FULL_STATEMENT_EXAMPLE.replacen(
+            "</NetStockPositionSummary>",
+            r#"<NetStockPosition accountId="U1234567" currency="USD" assetCategory="STK" symbol="GEO" conid="158655765" listingExchange="NYSE" netShares="1000" />
+                    </NetStockPositionSummary>"#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let result = &statements[0];
+
+        let positions = result.position_from_trades();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[&276343981], 1.0);
+
+        let mut overlapping = 0;
+        for net_position in &result.net_stock_positions {
+            if let Some(quantity) = positions.get(&net_position.conid) {
+                assert_eq!(*quantity, net_position.net_shares);
+                overlapping += 1;
+            }
+        }
+        assert_eq!(overlapping, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn section_counts_match_full_statement() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        assert_eq!(
+            result.section_counts(),
+            SectionCounts {
+                cash_reports: 3,
+                conversion_rates: 0,
+                equity_summaries: 2,
+                fifo_performance_summaries: 2,
+                funds_lines: 0,
+                net_stock_positions: 7,
+                open_positions: 7,
+                trades: 2,
+                trade_confirms: 0,
+            }
+        );
+        assert!(!result.is_empty());
+        assert!(Statement::builder("U1234567").build().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn trades_in_range_is_half_open() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        // ARGX executed at 10:19:55 EDT, GEO at 11:24:28 EDT.
+        let argx_ms = 1745590795000;
+        let trades = result.trades_in_range(argx_ms, argx_ms + 1000);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].ticker, "ARGX");
+
+        assert!(result.trades_in_range(argx_ms - 1000, argx_ms).is_empty());
+        assert_eq!(result.trades_in_range(argx_ms, i64::MAX).len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn turnover_and_volume_sum_trades() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let result = &statements[0];
+
+        let turnover = result.gross_turnover();
+        assert_eq!(turnover.len(), 1);
+        assert!((turnover[&Currency::USD] - money("31456.57")).abs() < money("0.000001"));
+        assert_eq!(result.share_volume(), 1001.0);
+        Ok(())
+    }
+
+    #[test]
+    fn net_liquidation_value_sums_positions_and_cash() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let statement = &statements[0];
+        let nlv = statement.net_liquidation_value();
+        assert_eq!(nlv.len(), 2);
+        assert!((nlv[&Currency::USD] - money("1808316.000917248")).abs() < money("0.000001"));
+        assert!((nlv[&Currency::CAD] - money("0.001153")).abs() < money("0.000001"));
+
+        // The equity summary total also includes the interest accruals.
+        let equity_summary = statement.sorted_equity_summaries().pop().unwrap();
+        assert!(
+            (nlv[&Currency::USD] + equity_summary.interest_accrual_mtd - equity_summary.total)
+                .abs()
+                < money("0.01")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sum_percent_of_nav_adds_position_weights() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        assert!((statements[0].sum_percent_of_nav() - 100.0).abs() < 0.001);
+        Ok(())
+    }
+
+    #[test]
+    fn account_id_returns_statement_account() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        assert_eq!(statements[0].account_id(), "U1234567");
+        Ok(())
+    }
+
+    #[test]
+    fn commissions_by_symbol_groups_by_ticker_and_currency() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let commissions = statements[0].commissions_by_symbol();
+        assert_eq!(commissions.len(), 2);
+        assert_eq!(
+            commissions[&("ARGX".to_string(), Currency::USD)],
+            money("-1.000035")
+        );
+        assert_eq!(
+            commissions[&("GEO".to_string(), Currency::USD)],
+            money("-5.035")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_trade_ids_reports_repeated_rows() -> Result<()> {
+        let mut statement = Parser::new()
+            .parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?
+            .remove(0);
+        assert!(statement.duplicate_trade_ids().is_empty());
+
+        let repeated = statement.trades[1].clone();
+        statement.trades.push(repeated.clone());
+        statement.trades.push(repeated);
+        assert_eq!(statement.duplicate_trade_ids(), vec!["32582764875"]);
+        Ok(())
+    }
+
+    #[test]
+    fn trades_by_date_groups_same_day_trades() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        let by_date = statements[0].trades_by_date();
+        assert_eq!(by_date.len(), 1);
+
+        let date = NaiveDate::from_ymd_opt(2025, 4, 25).unwrap();
+        let tickers: Vec<&str> = by_date[&date].iter().map(|t| t.ticker.as_str()).collect();
+        assert_eq!(tickers, vec!["ARGX", "GEO"]);
+        Ok(())
+    }
+
+    #[test]
+    fn trades_by_date_keys_on_trade_date() -> Result<()> {
+        // An execution on the evening of 2025-04-24 in New York that IBKR books on
+        // 2025-04-25, as for a fill on an Asian venue.
+        let xml = FULL_STATEMENT_EXAMPLE.replacen(
+            r#"dateTime="2025-04-25;10:19:55 EDT""#,
+            r#"dateTime="2025-04-24;21:15:00 EDT""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let by_date = statements[0].trades_by_date();
+        assert_eq!(by_date.len(), 1);
+
+        let date = NaiveDate::from_ymd_opt(2025, 4, 25).unwrap();
+        let tickers: Vec<&str> = by_date[&date].iter().map(|t| t.ticker.as_str()).collect();
+        assert_eq!(tickers, vec!["ARGX", "GEO"]);
+        Ok(())
+    }
+
+    #[test]
+    fn validate_reports_each_check() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        assert_eq!(statements[0].validate(), vec![]);
+
+        let xml = FULL_STATEMENT_EXAMPLE
+            .replacen(r#"netShares="3000""#, r#"netShares="2900""#, 1)
+            .replacen(
+                r#"<Trade accountId="U1234567""#,
+                r#"<Trade accountId="U7654321""#,
+                1,
+            );
+        let mut statement = Parser::new().parse_flex_query_response(&xml)?.remove(0);
+        let duplicate = statement.trades[1].clone();
+        statement.trades.push(duplicate);
+
+        let warnings = statement.validate();
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(
+            warnings[0],
+            ValidationWarning::AccountIdMismatch {
+                section: "Trade".to_string(),
+                account_id: "U7654321".to_string(),
+            }
+        );
+        assert!(matches!(
+            &warnings[1],
+            ValidationWarning::PositionMismatch(d) if d.ticker == "GRPN"
+        ));
+        assert_eq!(
+            warnings[2],
+            ValidationWarning::DuplicateTradeId("32582764875".to_string())
+        );
+        assert_eq!(
+            warnings[2].to_string(),
+            "trade 32582764875 appears more than once"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_requires_a_single_statement() -> Result<()> {
+        let statement: Statement = FULL_STATEMENT_EXAMPLE.parse()?;
+        assert_eq!(
+            statement,
+            Parser::new()
+                .parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?
+                .remove(0)
+        );
+
+        let second = r#"
+            <FlexStatement accountId="U7654321" fromDate="2025-04-25" toDate="2025-04-25">
+                <AccountInformation accountId="U7654321" />
+            </FlexStatement>
+        </FlexStatements>"#;
+        let xml = FULL_STATEMENT_EXAMPLE
+            .replacen(
+                r#"FlexStatements count="1""#,
+                r#"FlexStatements count="2""#,
+                1,
+            )
+            .replacen("</FlexStatements>", second, 1);
+        let error = xml.parse::<Statement>().unwrap_err();
+        assert!(
+            error.to_string().contains("exactly one FlexStatement"),
+            "{}",
+            error
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn summary_string_describes_statement() -> Result<()> {
+        let statement: Statement = FULL_STATEMENT_EXAMPLE.parse()?;
+        let summary = statement.to_summary_string();
+        assert!(summary.starts_with("Account U1234567, "));
+        assert!(summary.contains(", 2 trades, "));
+        assert!(summary.ends_with("Unrealized PnL: 86223.23 USD."));
+        assert_eq!(statement.to_string(), summary);
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+//! Borrowed views of a parsed Flex query response.
+//!
+//! [`FlexDocument`] keeps the parsed XML and hands out [`StatementRef`] and [`TradeRef`]
+//! views whose string accessors borrow from it, so scanning a large trade section does not
+//! allocate a `String` per attribute. Rows can still be converted into the owned types when
+//! needed.
+//!
+//! # Examples
+//!
+//! ```
+//! use ibkr_flex_statement::statement_ref::FlexDocument;
+//!
+//! let xml = r#"
+//!     <FlexQueryResponse queryName="example-query" type="AF">
+//!         <FlexStatements count="1">
+//!             <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25">
+//!                 <AccountInformation accountId="U1234567" />
+//!                 <Trades>
+//!                     <Trade accountId="U1234567" symbol="ARGX" ibExecID="0000edae.680b59d1.01.01" />
+//!                 </Trades>
+//!             </FlexStatement>
+//!         </FlexStatements>
+//!     </FlexQueryResponse>
+//! "#;
+//!
+//! let document = FlexDocument::parse(xml).unwrap();
+//! let statements = document.statements().unwrap();
+//! assert_eq!(statements[0].account_id(), Some("U1234567"));
+//!
+//! let tickers: Vec<&str> = statements[0].trades().filter_map(|t| t.ticker()).collect();
+//! assert_eq!(tickers, vec!["ARGX"]);
+//! ```
+
+use crate::node_utils::NodeWrapper;
+use crate::section_set::SectionSet;
+use crate::statement::Statement;
+use crate::statement_section::StatementSectionWithTimezone;
+use crate::trade::Trade;
+use crate::{Parser, flex_statement_nodes, parse_document};
+use anyhow::Result;
+use roxmltree::{Document, Node};
+
+/// A parsed Flex query response that [`StatementRef`]s borrow from.
+pub struct FlexDocument<'input> {
+    doc: Document<'input>,
+}
+
+impl<'input> FlexDocument<'input> {
+    /// Parses the XML of a Flex query response. Service error documents are reported as a
+    /// [`crate::error::FlexParseError`], as by [`Parser::parse_flex_query_response`].
+    pub fn parse(flex_query_response: &'input str) -> Result<Self> {
+        Ok(FlexDocument {
+            doc: parse_document(flex_query_response)?,
+        })
+    }
+
+    /// Returns a view of each `FlexStatement` in the document.
+    pub fn statements(&self) -> Result<Vec<StatementRef<'_>>> {
+        Ok(flex_statement_nodes(&self.doc)?
+            .into_iter()
+            .map(|node| StatementRef { node })
+            .collect())
+    }
+}
+
+/// A borrowed view of one `FlexStatement`.
+#[derive(Clone, Copy, Debug)]
+pub struct StatementRef<'a> {
+    node: Node<'a, 'a>,
+}
+
+impl<'a> StatementRef<'a> {
+    pub fn account_id(&self) -> Option<&'a str> {
+        attribute(self.node, "accountId")
+    }
+
+    /// Iterates over the statement's `Trade` rows without parsing them.
+    pub fn trades(&self) -> impl Iterator<Item = TradeRef<'a>> + use<'a> {
+        self.node
+            .descendants()
+            .filter(|n| n.tag_name().name() == "Trade")
+            .map(|node| TradeRef { node })
+    }
+
+    /// Parses the whole statement into an owned [`Statement`].
+    pub fn to_statement(&self, parser: &Parser) -> Result<Statement> {
+        parser.parse_flex_statement(&self.node, SectionSet::ALL, None)
+    }
+}
+
+/// A borrowed view of one `Trade` row. Accessors return `None` for missing or empty
+/// attributes.
+#[derive(Clone, Copy, Debug)]
+pub struct TradeRef<'a> {
+    node: Node<'a, 'a>,
+}
+
+impl<'a> TradeRef<'a> {
+    /// Returns the raw value of any attribute of the row.
+    pub fn attribute(&self, name: &str) -> Option<&'a str> {
+        attribute(self.node, name)
+    }
+
+    pub fn account_id(&self) -> Option<&'a str> {
+        self.attribute("accountId")
+    }
+
+    pub fn ticker(&self) -> Option<&'a str> {
+        self.attribute("symbol")
+    }
+
+    pub fn execution_id(&self) -> Option<&'a str> {
+        self.attribute("ibExecID")
+    }
+
+    pub fn order_id(&self) -> Option<&'a str> {
+        self.attribute("brokerageOrderID")
+    }
+
+    pub fn trade_id(&self) -> Option<&'a str> {
+        self.attribute("tradeID")
+    }
+
+    pub fn transaction_id(&self) -> Option<&'a str> {
+        self.attribute("transactionID")
+    }
+
+    /// Parses the row into an owned [`Trade`].
+    pub fn to_trade(&self, parser: &Parser) -> Result<Trade> {
+        Trade::from_node(&NodeWrapper { node: self.node }, &parser.timezone_map)
+    }
+}
+
+fn attribute<'a>(node: Node<'a, 'a>, name: &str) -> Option<&'a str> {
+    node.attribute(name)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::FULL_STATEMENT_EXAMPLE;
+
+    #[test]
+    fn trade_refs_match_parsed_trades() -> Result<()> {
+        let parser = Parser::new();
+        let document = FlexDocument::parse(FULL_STATEMENT_EXAMPLE)?;
+        let statements = document.statements()?;
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].account_id(), Some("U1234567"));
+
+        let trades: Vec<TradeRef> = statements[0].trades().collect();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[1].ticker(), Some("GEO"));
+        assert_eq!(trades[1].execution_id(), Some("00012e0e.680b7717.01.01"));
+        assert_eq!(trades[1].attribute("orderReference"), None);
+
+        let parsed = parser.parse_flex_query_response(FULL_STATEMENT_EXAMPLE)?;
+        assert_eq!(statements[0].to_statement(&parser)?, parsed[0]);
+        assert_eq!(trades[1].to_trade(&parser)?, parsed[0].trades[1]);
+        Ok(())
+    }
+}
@@ -1,16 +1,29 @@
-use crate::node_utils::NodeWrapper;
+use crate::node_utils::AttributeSource;
+use crate::time_utils::EodConvention;
 use anyhow::Result;
 use chrono_tz::Tz;
 use std::collections::HashMap;
 
 pub trait StatementSection {
-    fn from_node(node: &NodeWrapper) -> Result<Self>
+    fn from_node(node: &impl AttributeSource) -> Result<Self>
     where
         Self: Sized;
 }
 
 pub trait StatementSectionWithTimezone {
-    fn from_node(node: &NodeWrapper, timezone_map: &HashMap<String, Tz>) -> Result<Self>
+    fn from_node(node: &impl AttributeSource, timezone_map: &HashMap<String, Tz>) -> Result<Self>
+    where
+        Self: Sized;
+}
+
+/// A section whose rows carry end-of-day timestamps, placed at the close `eod_convention`
+/// selects.
+pub trait StatementSectionWithEod {
+    fn from_node(
+        node: &impl AttributeSource,
+        timezone_map: &HashMap<String, Tz>,
+        eod_convention: EodConvention,
+    ) -> Result<Self>
     where
         Self: Sized;
 }
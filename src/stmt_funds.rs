@@ -0,0 +1,152 @@
+use crate::currency::Currency;
+use crate::money::Money;
+use crate::node_utils::AttributeSource;
+use crate::statement_section::StatementSectionWithEod;
+use crate::time_utils::{self, EodConvention};
+use anyhow::Result;
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+/// A single cash movement from the statement of funds (`StmtFunds`) section.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FundsLine {
+    pub account_id: String,
+    pub activity_code: String,
+    pub activity_description: String,
+    pub amount: Money,
+    pub balance: Money,
+    pub conid: Option<u64>,
+    pub currency: Currency,
+    pub ticker: Option<String>,
+    pub timestamp_ms: i64,
+}
+
+/// Parses the row's `date`, which carries a time only when the query requests one; a bare
+/// date is placed at the close `eod_convention` selects.
+fn parse_funds_date_ms(
+    tz_map: &HashMap<String, Tz>,
+    eod_convention: EodConvention,
+    s: &str,
+) -> Result<i64> {
+    if s.contains(';') {
+        time_utils::parse_ibkr_datetime_ms(tz_map, s)
+    } else {
+        time_utils::trading_eod_timestamp_ms(s, eod_convention)
+    }
+}
+
+impl StatementSectionWithEod for FundsLine {
+    fn from_node(
+        node: &impl AttributeSource,
+        tz_map: &HashMap<String, Tz>,
+        eod_convention: EodConvention,
+    ) -> Result<FundsLine> {
+        Ok(FundsLine {
+            account_id: node.get_attribute("accountId")?,
+            activity_code: node.get_attribute("activityCode")?,
+            activity_description: node.get_attribute("activityDescription")?,
+            amount: node.parse_attribute("amount")?,
+            balance: node.parse_attribute("balance")?,
+            conid: node.parse_attribute_opt("conid")?,
+            currency: Currency::try_from(node.get_attribute("currency")?.as_str())?,
+            ticker: node.get_attribute_opt("symbol"),
+            timestamp_ms: parse_funds_date_ms(
+                tz_map,
+                eod_convention,
+                &node.get_attribute("date")?,
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::error::SectionError;
+    use crate::money::money;
+    use anyhow::Result;
+
+    const PARTIAL_STATEMENT_EXAMPLE: &str = r##"
+        <FlexQueryResponse queryName="example-query" type="AF">
+            <FlexStatements count="1">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25" period="LastBusinessDay" whenGenerated="2025-04-26;13:34:28 EDT">
+                    <AccountInformation accountId="U1234567" accountType="Individual" customerType="Individual" accountCapabilities="Portfolio Margin" tradingPermissions="Stocks,Options,Warrants,Forex,Futures,Crypto Currencies,Mutual Funds,Fully Paid Stock Loan" />
+                    <StmtFunds>
+                        <StatementOfFundsLine accountId="U1234567" currency="USD" assetCategory="" symbol="" description="" conid="" date="2025-04-25" settleDate="2025-04-25" activityCode="" activityDescription="Starting Balance" tradeID="" amount="0" debit="" credit="" balance="-1856140.99825062" levelOfDetail="Currency" reportDate="2025-04-25" />
+                        <StatementOfFundsLine accountId="U1234567" currency="USD" assetCategory="STK" symbol="GEO" description="THE GEO GROUP INC" conid="274115475" date="2025-04-25;10:02:15 EDT" settleDate="2025-04-28" activityCode="BUY" activityDescription="Buy 1,000 THE GEO GROUP INC " tradeID="7659132591" amount="-30850" debit="-30850" credit="" balance="-1886990.99825062" levelOfDetail="Currency" reportDate="2025-04-25" />
+                        <StatementOfFundsLine
+                            accountId="U1234567"
+                            currency="USD"
+                            assetCategory="STK"
+                            symbol="GEO"
+                            description="THE GEO GROUP INC"
+                            conid="274115475"
+                            date="2025-04-25;10:02:15 EDT"
+                            settleDate="2025-04-28"
+                            activityCode="OFEE"
+                            activityDescription="Commissions"
+                            tradeID="7659132591"
+                            amount="-5"
+                            debit="-5"
+                            credit=""
+                            balance="-1886995.99825062"
+                            levelOfDetail="Currency"
+                            reportDate="2025-04-25" />
+                    </StmtFunds>
+                </FlexStatement>
+            </FlexStatements>
+         </FlexQueryResponse>
+        "##;
+
+    #[test]
+    fn funds_line_missing_date_is_an_error() {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(r#" date="2025-04-25""#, "", 1);
+        let error = Parser::new().parse_flex_query_response(&xml).unwrap_err();
+        let section_error = error.downcast_ref::<SectionError>().unwrap();
+        assert_eq!(section_error.tag_name, "StatementOfFundsLine");
+        assert_eq!(section_error.error.to_string(), "missing attribute date");
+    }
+
+    #[test]
+    fn funds_lines_parse() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        assert_eq!(statements.len(), 1);
+        let result = &statements[0];
+
+        assert_eq!(result.funds_lines.len(), 3);
+
+        assert_eq!(
+            result.funds_lines[0],
+            FundsLine {
+                account_id: "U1234567".to_string(),
+                activity_code: "".to_string(),
+                activity_description: "Starting Balance".to_string(),
+                amount: money("0"),
+                balance: money("-1856140.99825062"),
+                conid: None,
+                currency: Currency::USD,
+                ticker: None,
+                timestamp_ms: result.funds_lines[0].timestamp_ms,
+            }
+        );
+
+        assert_eq!(
+            result.funds_lines[2],
+            FundsLine {
+                account_id: "U1234567".to_string(),
+                activity_code: "OFEE".to_string(),
+                activity_description: "Commissions".to_string(),
+                amount: money("-5"),
+                balance: money("-1886995.99825062"),
+                conid: Some(274115475),
+                currency: Currency::USD,
+                ticker: Some("GEO".to_string()),
+                // 2025-04-25 10:02:15 EDT
+                timestamp_ms: 1745589735000,
+            }
+        );
+        Ok(())
+    }
+}
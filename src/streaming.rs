@@ -0,0 +1,56 @@
+//! Pull-parser based iteration over trade rows, for responses too large to hold in memory.
+
+use crate::node_utils::StartTag;
+use crate::statement_section::StatementSectionWithTimezone;
+use crate::trade::Trade;
+use anyhow::Result;
+use chrono_tz::Tz;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::collections::HashMap;
+use std::io::BufRead;
+
+/// Yields each `Trade` row of a Flex query response as it is read. Created by
+/// [`crate::Parser::trade_iter`].
+pub(crate) struct TradeIter<'a, R: BufRead> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    timezone_map: &'a HashMap<String, Tz>,
+    done: bool,
+}
+
+impl<'a, R: BufRead> TradeIter<'a, R> {
+    pub(crate) fn new(reader: R, timezone_map: &'a HashMap<String, Tz>) -> Self {
+        TradeIter {
+            reader: Reader::from_reader(reader),
+            buf: Vec::new(),
+            timezone_map,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for TradeIter<'_, R> {
+    type Item = Result<Trade>;
+
+    fn next(&mut self) -> Option<Result<Trade>> {
+        while !self.done {
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf) {
+                Ok(Event::Start(e) | Event::Empty(e)) if e.local_name().as_ref() == b"Trade" => {
+                    return Some(
+                        StartTag::new(&e).and_then(|row| Trade::from_node(&row, self.timezone_map)),
+                    );
+                }
+                Ok(Event::Eof) => self.done = true,
+                Ok(_) => {}
+                Err(error) => {
+                    // The reader cannot recover from malformed XML, so stop after reporting it.
+                    self.done = true;
+                    return Some(Err(error.into()));
+                }
+            }
+        }
+        None
+    }
+}
@@ -1,39 +1,183 @@
+//! Conversions from IBKR dates and date/times to epoch milliseconds.
+//!
+//! Dates without a time are anchored to New York trading hours.
+
 use anyhow::Result;
-use chrono::{NaiveDate, /* NaiveDateTime, Timelike, */ TimeZone};
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, TimeZone};
 use chrono_tz::Tz;
+use std::collections::HashMap;
+
 pub fn timestamp_ms_at_hour(date: &str, timezone: Tz, hour: u32) -> Result<i64> {
-    let naive_dt = NaiveDate::parse_from_str(date, "%Y-%m-%d")?
-        .and_hms_opt(hour, 0, 0)
-        .unwrap();
-    let tz_aware_dt = timezone.from_local_datetime(&naive_dt).unwrap();
-    Ok(tz_aware_dt.timestamp() * 1000)
+    timestamp_ms_at_hour_minute(date, timezone, hour, 0)
 }
 
-/*
-
-pub fn timestamp_ms_at_hour_minute(date: &str, timezone: Tz, hour: u32, minute: u32) -> Result<i64> {
-    let naive_dt = NaiveDate::parse_from_str(date, "%Y-%m-%d")?.and_hms_opt(hour, minute, 0).unwrap();
-    let tz_aware_dt = timezone.from_local_datetime(&naive_dt).unwrap();
-    Ok(tz_aware_dt.timestamp() * 1000)
+/// Returns the instant of `hour:minute` local time on `date` (`yyyy-MM-dd`) in `timezone`.
+pub fn timestamp_ms_at_hour_minute(
+    date: &str,
+    timezone: Tz,
+    hour: u32,
+    minute: u32,
+) -> Result<i64> {
+    let naive_dt = NaiveDate::parse_from_str(date, "%Y-%m-%d")?
+        .and_hms_opt(hour, minute, 0)
+        .ok_or_else(|| anyhow::anyhow!("invalid time of day {:02}:{:02}", hour, minute))?;
+    Ok(resolve_local(timezone, &naive_dt)?.timestamp_millis())
 }
 
+/// Returns 04:00 New York time on `date`, when pre-market trading opens.
 pub fn trading_sod_pre_market_timestamp_ms(date: &str) -> Result<i64> {
     timestamp_ms_at_hour(date, chrono_tz::America::New_York, 4)
 }
 
- */
+/// Returns 09:30 New York time on `date`, when the regular session opens.
+pub fn trading_sod_timestamp_ms(date: &str) -> Result<i64> {
+    timestamp_ms_at_hour_minute(date, chrono_tz::America::New_York, 9, 30)
+}
 
 pub fn trading_eod_after_hours_timestamp_ms(date: &str) -> Result<i64> {
-    timestamp_ms_at_hour(date, chrono_tz::America::New_York, 20)
+    trading_eod_timestamp_ms(date, EodConvention::AfterHours)
 }
 
-/*
-pub fn trading_sod_timestamp_ms(date: &str) -> Result<i64> {
-    timestamp_ms_at_hour_minute(date, chrono_tz::America::New_York, 9, 30)
+/// Returns 16:00 New York time on `date`, when the regular session closes.
+pub fn trading_eod_regular_close_timestamp_ms(date: &str) -> Result<i64> {
+    trading_eod_timestamp_ms(date, EodConvention::RegularClose)
+}
+
+/// Returns the New York close selected by `eod_convention` on `date`.
+pub fn trading_eod_timestamp_ms(date: &str, eod_convention: EodConvention) -> Result<i64> {
+    timestamp_ms_at_hour(date, chrono_tz::America::New_York, eod_convention.hour())
+}
+
+/// Which New York close the end-of-day timestamps of a statement, such as
+/// `timestamp_eod_ms`, refer to.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EodConvention {
+    /// 20:00, when after-hours trading ends.
+    #[default]
+    AfterHours,
+    /// 16:00, when the regular session closes.
+    RegularClose,
+}
+
+impl EodConvention {
+    /// Returns the New York hour of the close.
+    pub fn hour(self) -> u32 {
+        match self {
+            Self::AfterHours => 20,
+            Self::RegularClose => 16,
+        }
+    }
+}
+
+/// Parses an IBKR `date;time TZ` value, e.g. `2025-04-25;10:19:55 EDT`, into epoch
+/// milliseconds, resolving the timezone abbreviation through `tz_map`.
+///
+/// A time repeated when daylight saving ends is resolved to its earlier instant. Times
+/// skipped when it starts, unknown abbreviations, and malformed values are errors.
+pub fn parse_ibkr_datetime_ms(tz_map: &HashMap<String, Tz>, s: &str) -> Result<i64> {
+    let (datetime_str, short_timezone) = s
+        .split_once(' ')
+        .ok_or_else(|| anyhow::anyhow!("missing timezone in date/time {}", s))?;
+    let timezone = tz_map
+        .get(short_timezone)
+        .ok_or_else(|| anyhow::anyhow!("unknown timezone {} in date/time {}", short_timezone, s))?;
+
+    let naive_dt = NaiveDateTime::parse_from_str(datetime_str, "%Y-%m-%d;%H:%M:%S")?;
+    Ok(resolve_local(*timezone, &naive_dt)?.timestamp_millis())
 }
 
-pub fn trading_eod_timestamp_ms(date: &str) -> Result<i64> {
-    timestamp_ms_at_hour(date, chrono_tz::America::New_York, 20)
+/// Returns the New York calendar date containing the given instant.
+pub fn new_york_date_from_timestamp_ms(timestamp_ms: i64) -> Option<NaiveDate> {
+    DateTime::from_timestamp_millis(timestamp_ms)
+        .map(|dt| dt.with_timezone(&chrono_tz::America::New_York).date_naive())
 }
 
- */
+/// Resolves a local time around daylight saving transitions: a repeated time maps to its
+/// earlier instant and a skipped time is an error.
+fn resolve_local(timezone: Tz, naive_dt: &NaiveDateTime) -> Result<DateTime<Tz>> {
+    match timezone.from_local_datetime(naive_dt) {
+        LocalResult::Single(dt) => Ok(dt),
+        LocalResult::Ambiguous(earliest, _) => Ok(earliest),
+        LocalResult::None => Err(anyhow::anyhow!(
+            "local time {} does not exist in {}",
+            naive_dt,
+            timezone
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_york_tz_map() -> HashMap<String, Tz> {
+        HashMap::from([
+            ("EST".to_string(), chrono_tz::America::New_York),
+            ("EDT".to_string(), chrono_tz::America::New_York),
+        ])
+    }
+
+    #[test]
+    fn ibkr_datetime_parses() -> Result<()> {
+        let ms = parse_ibkr_datetime_ms(&new_york_tz_map(), "2025-04-25;10:19:55 EDT")?;
+        assert_eq!(ms, 1745590795000);
+        Ok(())
+    }
+
+    #[test]
+    fn ambiguous_ibkr_datetime_resolves_to_earlier_instant() -> Result<()> {
+        // 01:30 occurs twice on 2025-11-02 in New York; the first is 05:30 UTC.
+        let ms = parse_ibkr_datetime_ms(&new_york_tz_map(), "2025-11-02;01:30:00 EDT")?;
+        assert_eq!(ms, 1762061400000);
+        Ok(())
+    }
+
+    #[test]
+    fn nonexistent_ibkr_datetime_is_an_error() {
+        // 02:30 is skipped on 2025-03-09 in New York.
+        assert!(parse_ibkr_datetime_ms(&new_york_tz_map(), "2025-03-09;02:30:00 EST").is_err());
+    }
+
+    #[test]
+    fn unknown_timezone_is_an_error() {
+        let error =
+            parse_ibkr_datetime_ms(&new_york_tz_map(), "2025-04-25;10:19:55 CEST").unwrap_err();
+        assert!(error.to_string().contains("unknown timezone CEST"));
+        assert!(parse_ibkr_datetime_ms(&new_york_tz_map(), "2025-04-25;10:19:55").is_err());
+    }
+
+    #[test]
+    fn session_start_timestamps() -> Result<()> {
+        // 2025-04-25 is on daylight time (UTC-4).
+        assert_eq!(
+            trading_sod_pre_market_timestamp_ms("2025-04-25")?,
+            1745568000000
+        );
+        assert_eq!(trading_sod_timestamp_ms("2025-04-25")?, 1745587800000);
+        Ok(())
+    }
+
+    #[test]
+    fn session_start_timestamps_on_dst_transition_days() -> Result<()> {
+        // Daylight time starts at 02:00 on 2025-03-09, before pre-market opens (UTC-4).
+        assert_eq!(
+            trading_sod_pre_market_timestamp_ms("2025-03-09")?,
+            1741507200000
+        );
+        assert_eq!(trading_sod_timestamp_ms("2025-03-09")?, 1741527000000);
+        // Daylight time ends at 02:00 on 2025-11-02 (UTC-5).
+        assert_eq!(
+            trading_sod_pre_market_timestamp_ms("2025-11-02")?,
+            1762074000000
+        );
+        assert_eq!(trading_sod_timestamp_ms("2025-11-02")?, 1762093800000);
+        Ok(())
+    }
+
+    #[test]
+    fn skipped_local_time_is_an_error() {
+        let new_york = chrono_tz::America::New_York;
+        assert!(timestamp_ms_at_hour_minute("2025-03-09", new_york, 2, 30).is_err());
+        assert!(timestamp_ms_at_hour_minute("2025-04-25", new_york, 24, 0).is_err());
+    }
+}
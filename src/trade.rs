@@ -1,27 +1,34 @@
-use crate::{node_utils::NodeWrapper, statement_section::StatementSectionWithTimezone};
+use crate::{node_utils::AttributeSource, statement_section::StatementSectionWithTimezone};
 
 use super::currency::Currency;
+use crate::exchange::Exchange;
+use crate::money::Money;
+use crate::time_utils;
 use anyhow::Result;
-use chrono::{NaiveDateTime, TimeZone};
+use chrono::NaiveDate;
 use chrono_tz::Tz;
 use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TradeSide {
     Buy,
     Sell,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OpenCloseIndicator {
     Close,
     CloseOpen,
-    // TODO figure out what "" (none) means
+    /// The indicator is empty or missing, as on forex conversions and some other
+    /// transaction types that neither open nor close a position.
     None,
     Open,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OrderType {
     Limit,
     Market,
@@ -29,23 +36,139 @@ pub enum OrderType {
     Stop,
 }
 
-#[derive(Debug, PartialEq)]
+/// The `levelOfDetail` of a trade row. Rows without the attribute are treated as
+/// [`LevelOfDetail::Execution`], matching the recommended Flex query configuration.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LevelOfDetail {
+    AssetSummary,
+    ClosedLot,
+    Execution,
+    Order,
+    SymbolSummary,
+}
+
+/// A code from a trade's `notes` attribute.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TradeCode {
+    Assignment,
+    Closing,
+    MultipleLot,
+    Opening,
+    PartialExecution,
+    Other(String),
+}
+
+/// The `transactionType` of a trade row.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransactionType {
+    /// An internal transfer booked by IBKR rather than executed on an exchange, e.g. from
+    /// an option exercise or a corporate action.
+    BookTrade,
+    /// A fill executed on an exchange.
+    ExchTrade,
+    FracShare,
+    FracShareCancel,
+    TradeCancel,
+    Other(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Trade {
     pub account_id: String,
-    pub conid: u32,
+    pub close_price: Money,
+    pub codes: Vec<TradeCode>,
+    pub conid: u64,
     pub currency: Currency,
     pub execution_exchange: String,
     pub execution_id: String,
     pub execution_timestamp_ms: i64,
-    pub commission: f64,
+    pub commission: Money,
+    /// From `ibCommissionCurrency`, or `currency` when the query omits that column.
+    pub commission_currency: Currency,
+    /// Realized FIFO profit or loss of a closing trade; 0 for opening trades.
+    pub fifo_pnl_realized: Money,
+    pub ib_order_id: String,
+    /// Set when the order was submitted through the API rather than manually.
+    pub is_api_order: bool,
+    /// Set when IBKR marks the row as a cancellation, e.g. `buySell="BUY (Ca.)"`.
+    pub is_cancel: bool,
+    pub level_of_detail: LevelOfDetail,
     pub listing_exchange: String,
     pub open_close_indicator: OpenCloseIndicator,
     pub order_id: String,
+    /// The client-side reference attached to the order, if any.
+    pub order_reference: Option<String>,
+    /// When the order was placed; empty for some rows.
+    pub order_timestamp_ms: Option<i64>,
     pub order_type: OrderType,
-    pub price: f64,
+    pub price: Money,
+    /// Cash received for the trade before commission: negative for buys, positive for
+    /// sells.
+    pub proceeds: Money,
     pub quantity: f64,
     pub side: TradeSide,
     pub ticker: String,
+    /// The `tradeDate` IBKR books the trade on, which can differ from the New York date
+    /// of `execution_timestamp_ms`, e.g. for executions on Asian venues.
+    pub trade_date: NaiveDate,
+    pub trade_id: Option<String>,
+    pub trade_money: Money,
+    pub transaction_id: Option<String>,
+    pub transaction_type: TransactionType,
+}
+
+impl TradeSide {
+    pub fn is_buy(&self) -> bool {
+        *self == Self::Buy
+    }
+
+    pub fn is_sell(&self) -> bool {
+        *self == Self::Sell
+    }
+}
+
+impl Trade {
+    /// Returns the quantity signed by side: positive for buys and negative for sells.
+    ///
+    /// IBKR already reports sells with a negative `quantity`; the sign is taken from the
+    /// side so the result does not depend on that.
+    pub fn signed_quantity(&self) -> f64 {
+        match self.side {
+            TradeSide::Buy => self.quantity.abs(),
+            TradeSide::Sell => -self.quantity.abs(),
+        }
+    }
+
+    /// Returns the venue the trade executed on, parsed from `execution_exchange`.
+    pub fn execution_venue(&self) -> Exchange {
+        Exchange::from(self.execution_exchange.as_str())
+    }
+
+    /// Returns the instrument's primary listing venue, parsed from `listing_exchange`.
+    pub fn listing_venue(&self) -> Exchange {
+        Exchange::from(self.listing_exchange.as_str())
+    }
+
+    /// Returns the cash impact of the trade net of commission, `proceeds + commission`.
+    /// IBKR reports commissions as negative amounts, so a sale nets less than its proceeds.
+    ///
+    /// The commission is added as is, so the result is only meaningful when
+    /// `commission_currency` matches `currency`.
+    pub fn net_proceeds(&self) -> Money {
+        self.proceeds + self.commission
+    }
+
+    /// Returns the key identifying this row across overlapping statements.
+    ///
+    /// This is the `transactionID`, which IBKR assigns to every trade row, including
+    /// cancellations of an earlier trade. Rows without one fall back to the `ibExecID`.
+    pub fn dedup_key(&self) -> &str {
+        self.transaction_id.as_deref().unwrap_or(&self.execution_id)
+    }
 }
 
 impl<'a> TryFrom<&'a str> for OpenCloseIndicator {
@@ -64,6 +187,46 @@ impl<'a> TryFrom<&'a str> for OpenCloseIndicator {
     }
 }
 
+impl<'a> TryFrom<&'a str> for LevelOfDetail {
+    type Error = anyhow::Error;
+    fn try_from(s: &'a str) -> Result<Self> {
+        match s {
+            "ASSET_SUMMARY" => Ok(Self::AssetSummary),
+            "CLOSED_LOT" => Ok(Self::ClosedLot),
+            "EXECUTION" => Ok(Self::Execution),
+            "ORDER" => Ok(Self::Order),
+            "SYMBOL_SUMMARY" => Ok(Self::SymbolSummary),
+            _ => Err(anyhow::Error::msg(format!("unknown level of detail {}", s))),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for TradeCode {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "A" => Self::Assignment,
+            "C" => Self::Closing,
+            "ML" => Self::MultipleLot,
+            "O" => Self::Opening,
+            "P" => Self::PartialExecution,
+            _ => Self::Other(s.to_string()),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for TransactionType {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "BookTrade" => Self::BookTrade,
+            "ExchTrade" => Self::ExchTrade,
+            "FracShare" => Self::FracShare,
+            "FracShareCancel" => Self::FracShareCancel,
+            "TradeCancel" => Self::TradeCancel,
+            _ => Self::Other(s.to_string()),
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a str> for OrderType {
     type Error = anyhow::Error;
     fn try_from(s: &'a str) -> Result<Self> {
@@ -89,44 +252,97 @@ impl<'a> TryFrom<&'a str> for TradeSide {
     }
 }
 
-fn try_parse_trade_execution_time_ms(tz_map: &HashMap<String, Tz>, s: &str) -> Result<i64> {
-    let mut dt_parts = s.split(" ");
-    let datetime_str = dt_parts.next().unwrap();
-
-    let short_timezone = dt_parts.next().unwrap();
-    let timezone = tz_map.get(short_timezone).unwrap();
-
-    let naive_dt = NaiveDateTime::parse_from_str(datetime_str, "%Y-%m-%d;%H:%M:%S %Z")?;
-    let tz_aware_dt = timezone.from_local_datetime(&naive_dt).unwrap();
+/// Parses a `buySell` value into the trade side and whether the row is a cancellation.
+pub(crate) fn parse_buy_sell(s: &str) -> Result<(TradeSide, bool)> {
+    match s.strip_suffix(" (Ca.)") {
+        Some(side) => Ok((TradeSide::try_from(side)?, true)),
+        None => Ok((TradeSide::try_from(s)?, false)),
+    }
+}
 
-    // println!("tz_aware_dt: {:?}, timestamp: {}", tz_aware_dt, tz_aware_dt.timestamp());
+/// Parses an `isAPIOrder` value. A missing or empty value is treated as a manual order.
+pub(crate) fn parse_is_api_order(node: &impl AttributeSource) -> Result<bool> {
+    match node.get_attribute_opt("isAPIOrder").as_deref() {
+        Some("Y") => Ok(true),
+        Some("N") | None => Ok(false),
+        Some(s) => Err(anyhow::Error::msg(format!(
+            "unknown isAPIOrder value {}",
+            s
+        ))),
+    }
+}
 
-    Ok(tz_aware_dt.timestamp() * 1000)
+/// Parses the semicolon-delimited `notes` (or older `code`) attribute into trade codes.
+pub(crate) fn parse_trade_codes(node: &impl AttributeSource) -> Vec<TradeCode> {
+    node.get_attribute_opt("notes")
+        .or_else(|| node.get_attribute_opt("code"))
+        .map(|notes| {
+            notes
+                .split(';')
+                .filter(|code| !code.is_empty())
+                .map(TradeCode::from)
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 impl StatementSectionWithTimezone for Trade {
-    fn from_node(node: &NodeWrapper, tz_map: &HashMap<String, Tz>) -> Result<Trade> {
+    fn from_node(node: &impl AttributeSource, tz_map: &HashMap<String, Tz>) -> Result<Trade> {
+        let (side, is_cancel) = parse_buy_sell(&node.get_attribute("buySell")?)?;
+        let currency = Currency::try_from(node.get_attribute("currency")?.as_str())?;
+
         Ok(Trade {
             account_id: node.get_attribute("accountId")?,
+            close_price: node.parse_attribute("closePrice")?,
+            codes: parse_trade_codes(node),
             commission: node.parse_attribute("ibCommission")?,
+            commission_currency: node
+                .get_attribute_opt("ibCommissionCurrency")
+                .map(|s| Currency::try_from(s.as_str()))
+                .transpose()?
+                .unwrap_or_else(|| currency.clone()),
             conid: node.parse_attribute("conid")?,
-            currency: Currency::try_from(node.node.attribute("currency").unwrap())?,
+            currency,
             execution_exchange: node.get_attribute("exchange")?,
             execution_id: node.get_attribute("ibExecID")?,
-            execution_timestamp_ms: try_parse_trade_execution_time_ms(
+            execution_timestamp_ms: time_utils::parse_ibkr_datetime_ms(
                 tz_map,
-                node.node.attribute("dateTime").unwrap(),
+                &node.get_attribute("dateTime")?,
             )?,
+            fifo_pnl_realized: node.parse_attribute("fifoPnlRealized")?,
+            ib_order_id: node.get_attribute("ibOrderID")?,
+            is_api_order: parse_is_api_order(node)?,
+            is_cancel,
+            level_of_detail: node
+                .get_attribute_opt("levelOfDetail")
+                .map(|s| LevelOfDetail::try_from(s.as_str()))
+                .transpose()?
+                .unwrap_or(LevelOfDetail::Execution),
             listing_exchange: node.get_attribute("listingExchange")?,
-            open_close_indicator: OpenCloseIndicator::try_from(
-                node.node.attribute("openCloseIndicator").unwrap(),
-            )?,
+            open_close_indicator: node
+                .get_attribute_opt("openCloseIndicator")
+                .map(|s| OpenCloseIndicator::try_from(s.as_str()))
+                .transpose()?
+                .unwrap_or(OpenCloseIndicator::None),
             order_id: node.get_attribute("brokerageOrderID")?,
-            order_type: OrderType::try_from(node.node.attribute("orderType").unwrap())?,
+            order_reference: node.get_attribute_opt("orderReference"),
+            order_timestamp_ms: node
+                .get_attribute_opt("orderTime")
+                .map(|s| time_utils::parse_ibkr_datetime_ms(tz_map, &s))
+                .transpose()?,
+            order_type: OrderType::try_from(node.get_attribute("orderType")?.as_str())?,
             price: node.parse_attribute("tradePrice")?,
+            proceeds: node.parse_attribute("proceeds")?,
             quantity: node.parse_attribute("quantity")?,
-            side: TradeSide::try_from(node.node.attribute("buySell").unwrap())?,
+            side,
             ticker: node.get_attribute("symbol")?,
+            trade_date: NaiveDate::parse_from_str(&node.get_attribute("tradeDate")?, "%Y-%m-%d")?,
+            trade_id: node.get_attribute_opt("tradeID"),
+            trade_money: node.parse_attribute("tradeMoney")?,
+            transaction_id: node.get_attribute_opt("transactionID"),
+            transaction_type: TransactionType::from(
+                node.get_attribute("transactionType")?.as_str(),
+            ),
         })
     }
 }
@@ -135,6 +351,8 @@ impl StatementSectionWithTimezone for Trade {
 mod tests {
     use super::*;
     use crate::Parser;
+    use crate::error::SectionError;
+    use crate::money::money;
     use anyhow::Result;
 
     const PARTIAL_STATEMENT_EXAMPLE: &str = r##"
@@ -239,7 +457,7 @@ mod tests {
 
     #[test]
     fn trades_parse() -> Result<()> {
-        let statements = Parser::new()?.parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
         assert_eq!(statements.len(), 1);
         let result = &statements[0];
 
@@ -251,19 +469,36 @@ mod tests {
             result.trades[0],
             Trade {
                 account_id: "U1234567".to_string(),
-                commission: -1.000035,
+                close_price: money("614.76"),
+                codes: vec![],
+                commission: money("-1.000035"),
+                commission_currency: Currency::USD,
                 conid: 276343981,
                 currency: Currency::USD,
                 execution_exchange: "BYX".to_string(),
                 execution_id: "0000edae.680b59d1.01.01".to_string(),
                 execution_timestamp_ms: result.trades[0].execution_timestamp_ms,
+                fifo_pnl_realized: money("0"),
+                ib_order_id: "4015030800".to_string(),
+                is_api_order: false,
+                is_cancel: false,
+                level_of_detail: LevelOfDetail::Execution,
                 open_close_indicator: OpenCloseIndicator::Open,
                 order_id: "002ce642.00014b44.680b0ed6.0001".to_string(),
+                order_reference: None,
+                // 2025-04-25 10:19:55 EDT
+                order_timestamp_ms: Some(1745590795000),
                 order_type: OrderType::Limit,
-                price: 606.57,
+                price: money("606.57"),
+                proceeds: money("-606.57"),
                 quantity: 1.0,
                 side: TradeSide::Buy,
                 ticker: "ARGX".to_string(),
+                trade_date: NaiveDate::from_ymd_opt(2025, 4, 25).unwrap(),
+                trade_id: Some("7587063231".to_string()),
+                trade_money: money("606.57"),
+                transaction_id: Some("32580112485".to_string()),
+                transaction_type: TransactionType::ExchTrade,
                 listing_exchange: "NASDAQ".to_string(),
             }
         );
@@ -273,22 +508,303 @@ mod tests {
             result.trades[1],
             Trade {
                 account_id: "U1234567".to_string(),
-                commission: -5.035,
+                close_price: money("30.58"),
+                codes: vec![],
+                commission: money("-5.035"),
+                commission_currency: Currency::USD,
                 conid: 158655765,
                 currency: Currency::USD,
                 execution_exchange: "NYSE".to_string(),
                 execution_id: "00012e0e.680b7717.01.01".to_string(),
                 execution_timestamp_ms: result.trades[1].execution_timestamp_ms,
+                fifo_pnl_realized: money("0"),
+                ib_order_id: "4015577648".to_string(),
+                is_api_order: false,
+                is_cancel: false,
+                level_of_detail: LevelOfDetail::Execution,
                 open_close_indicator: OpenCloseIndicator::Open,
                 order_id: "002ce642.00014b44.680b0fbf.0001".to_string(),
+                order_reference: None,
+                // 2025-04-25 11:24:26 EDT
+                order_timestamp_ms: Some(1745594666000),
                 order_type: OrderType::Limit,
-                price: 30.85,
+                price: money("30.85"),
+                proceeds: money("-30850"),
                 quantity: 1000.0,
                 side: TradeSide::Buy,
                 ticker: "GEO".to_string(),
+                trade_date: NaiveDate::from_ymd_opt(2025, 4, 25).unwrap(),
+                trade_id: Some("7587946875".to_string()),
+                trade_money: money("30850"),
+                transaction_id: Some("32582764875".to_string()),
+                transaction_type: TransactionType::ExchTrade,
                 listing_exchange: "NYSE".to_string(),
             }
         );
         Ok(())
     }
+
+    #[test]
+    fn commission_currency_parses() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        for trade in &statements[0].trades {
+            assert_eq!(trade.commission_currency, Currency::USD);
+        }
+
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"ibCommissionCurrency="USD""#,
+            r#"ibCommissionCurrency="CAD""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        assert_eq!(statements[0].trades[0].commission_currency, Currency::CAD);
+        assert_eq!(statements[0].trades[0].currency, Currency::USD);
+
+        let xml = PARTIAL_STATEMENT_EXAMPLE
+            .replace(r#" ibCommissionCurrency="USD""#, "")
+            .replacen(r#"currency="USD""#, r#"currency="CAD""#, 1);
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let trades = &statements[0].trades;
+        assert!(!trades.is_empty());
+        for trade in trades {
+            assert_eq!(trade.commission_currency, trade.currency);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn missing_required_attribute_is_an_error() {
+        for attribute in [
+            r#"buySell="BUY""#,
+            r#"currency="USD""#,
+            r#"dateTime="2025-04-25;10:19:55 EDT""#,
+            r#"orderType="LMT""#,
+        ] {
+            let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(attribute, "", 1);
+            let error = Parser::new().parse_flex_query_response(&xml).unwrap_err();
+            let section_error = error.downcast_ref::<SectionError>().unwrap();
+            let name = attribute.split('=').next().unwrap();
+            assert_eq!(
+                section_error.error.to_string(),
+                format!("missing attribute {}", name)
+            );
+        }
+    }
+
+    #[test]
+    fn empty_order_time_parses_as_none() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"orderTime="2025-04-25;10:19:55 EDT""#,
+            r#"orderTime="""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        assert_eq!(statements[0].trades[0].order_timestamp_ms, None);
+        assert_eq!(
+            statements[0].trades[1].order_timestamp_ms,
+            Some(1745594666000)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cancelled_trade_parses() -> Result<()> {
+        let cancelled =
+            PARTIAL_STATEMENT_EXAMPLE.replacen(r#"buySell="BUY""#, r#"buySell="BUY (Ca.)""#, 1);
+        let statements = Parser::new().parse_flex_query_response(&cancelled)?;
+        let result = &statements[0];
+
+        assert_eq!(result.trades[0].side, TradeSide::Buy);
+        assert!(result.trades[0].is_cancel);
+        assert_eq!(result.trades[1].side, TradeSide::Buy);
+        assert!(!result.trades[1].is_cancel);
+        Ok(())
+    }
+
+    #[test]
+    fn trade_codes_parse() -> Result<()> {
+        let with_notes = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"openCloseIndicator="O""#,
+            r#"openCloseIndicator="O" notes="O;P;XYZ""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&with_notes)?;
+        let result = &statements[0];
+
+        assert_eq!(
+            result.trades[0].codes,
+            vec![
+                TradeCode::Opening,
+                TradeCode::PartialExecution,
+                TradeCode::Other("XYZ".to_string())
+            ]
+        );
+        assert!(result.trades[1].codes.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn level_of_detail_filters() -> Result<()> {
+        let mixed = PARTIAL_STATEMENT_EXAMPLE
+            .replacen(
+                r#"transactionType="ExchTrade""#,
+                r#"transactionType="ExchTrade" levelOfDetail="EXECUTION""#,
+                1,
+            )
+            .replacen(
+                r#"symbol="GEO""#,
+                r#"symbol="GEO" levelOfDetail="ORDER""#,
+                1,
+            );
+        let statements = Parser::new().parse_flex_query_response(&mixed)?;
+        let result = &statements[0];
+
+        assert_eq!(result.trades[0].level_of_detail, LevelOfDetail::Execution);
+        assert_eq!(result.trades[1].level_of_detail, LevelOfDetail::Order);
+
+        let executions = result.executions();
+        assert_eq!(executions.len(), 1);
+        assert_eq!(executions[0].ticker, "ARGX");
+
+        let orders = result.orders();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].ticker, "GEO");
+        Ok(())
+    }
+
+    #[test]
+    fn transaction_type_filters() -> Result<()> {
+        let mixed = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"transactionType="ExchTrade""#,
+            r#"transactionType="BookTrade""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&mixed)?;
+        let result = &statements[0];
+
+        assert_eq!(
+            result.trades[0].transaction_type,
+            TransactionType::BookTrade
+        );
+        assert_eq!(
+            result.trades[1].transaction_type,
+            TransactionType::ExchTrade
+        );
+
+        let exchange_trades = result.exchange_trades();
+        assert_eq!(exchange_trades.len(), 1);
+        assert_eq!(exchange_trades[0].ticker, "GEO");
+
+        let book_trades = result.book_trades();
+        assert_eq!(book_trades.len(), 1);
+        assert_eq!(book_trades[0].ticker, "ARGX");
+        Ok(())
+    }
+
+    #[test]
+    fn signed_quantity_follows_side() -> Result<()> {
+        // Turn the second trade, GEO, into a sell.
+        let (argx, geo) = PARTIAL_STATEMENT_EXAMPLE
+            .split_at(PARTIAL_STATEMENT_EXAMPLE.find(r#"symbol="GEO""#).unwrap());
+        let geo = geo
+            .replacen(r#"quantity="1000""#, r#"quantity="-1000""#, 1)
+            .replacen(r#"buySell="BUY""#, r#"buySell="SELL""#, 1);
+        let xml = format!("{}{}", argx, geo);
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let result = &statements[0];
+
+        assert!(result.trades[0].side.is_buy());
+        assert_eq!(result.trades[0].signed_quantity(), 1.0);
+        assert!(result.trades[1].side.is_sell());
+        assert_eq!(result.trades[1].signed_quantity(), -1000.0);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_open_close_indicator_parses_as_none() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"openCloseIndicator="O""#,
+            r#"openCloseIndicator="""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let result = &statements[0];
+
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(
+            result.trades[0].open_close_indicator,
+            OpenCloseIndicator::None
+        );
+        assert_eq!(
+            result.trades[1].open_close_indicator,
+            OpenCloseIndicator::Open
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn is_api_order_parses() -> Result<()> {
+        let statements = Parser::new().parse_flex_query_response(PARTIAL_STATEMENT_EXAMPLE)?;
+        assert!(statements[0].trades.iter().all(|t| !t.is_api_order));
+
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(r#"isAPIOrder="N""#, r#"isAPIOrder="Y""#, 1);
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        assert!(statements[0].trades[0].is_api_order);
+
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(r#"isAPIOrder="N""#, r#"isAPIOrder="X""#, 1);
+        assert!(Parser::new().parse_flex_query_response(&xml).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn order_reference_parses() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE.replacen(
+            r#"orderReference="""#,
+            r#"orderReference="momentum-42""#,
+            1,
+        );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let result = &statements[0];
+
+        assert_eq!(
+            result.trades[0].order_reference,
+            Some("momentum-42".to_string())
+        );
+        assert_eq!(result.trades[1].order_reference, None);
+        Ok(())
+    }
+
+    #[test]
+    fn net_proceeds_deducts_commission() -> Result<()> {
+        let xml = PARTIAL_STATEMENT_EXAMPLE
+            .replacen(r#"quantity="1000""#, r#"quantity="-1000""#, 1)
+            .replacen(r#"proceeds="-30850""#, r#"proceeds="30850""#, 1)
+            .replacen(
+                r#"openCloseIndicator="O"
+                               cost="30855.035"
+                               fifoPnlRealized="0""#,
+                r#"openCloseIndicator="C"
+                               cost="-30650"
+                               fifoPnlRealized="194.965""#,
+                1,
+            )
+            .replacen(
+                r#"buySell="BUY"
+                               ibOrderID="4015577648""#,
+                r#"buySell="SELL"
+                               ibOrderID="4015577648""#,
+                1,
+            );
+        let statements = Parser::new().parse_flex_query_response(&xml)?;
+        let sell = &statements[0].trades[1];
+
+        assert_eq!(sell.side, TradeSide::Sell);
+        assert_eq!(sell.proceeds, money("30850"));
+        assert!((sell.net_proceeds() - money("30844.965")).abs() < money("0.000001"));
+        assert_eq!(sell.fifo_pnl_realized, money("194.965"));
+
+        let buy = &statements[0].trades[0];
+        assert!((buy.net_proceeds() - money("-607.570035")).abs() < money("0.000001"));
+        Ok(())
+    }
 }
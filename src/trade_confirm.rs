@@ -0,0 +1,182 @@
+use crate::asset_category::AssetCategory;
+use crate::currency::Currency;
+use crate::money::Money;
+use crate::node_utils::AttributeSource;
+use crate::statement_section::StatementSectionWithTimezone;
+use crate::time_utils;
+use crate::trade::{self, OrderType, TradeCode, TradeSide};
+use anyhow::Result;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+/// A `TradeConfirm` row from a trade confirmation report, `type="TF"`.
+///
+/// Confirmations describe the same fills as activity [`crate::trade::Trade`] rows but are
+/// available intraday, before close prices and realized PnL are known, and name several
+/// attributes differently, e.g. `price` for `tradePrice` and `execID` for `ibExecID`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TradeConfirm {
+    pub account_id: String,
+    pub asset_category: AssetCategory,
+    pub codes: Vec<TradeCode>,
+    pub commission: Money,
+    pub commission_currency: Currency,
+    pub conid: u64,
+    pub currency: Currency,
+    pub execution_exchange: String,
+    pub execution_id: String,
+    pub execution_timestamp_ms: i64,
+    pub ib_order_id: String,
+    pub is_api_order: bool,
+    pub is_cancel: bool,
+    pub listing_exchange: String,
+    pub net_cash: Money,
+    pub order_id: String,
+    pub order_reference: Option<String>,
+    pub order_type: OrderType,
+    pub price: Money,
+    pub proceeds: Money,
+    pub quantity: f64,
+    pub settle_date: Option<String>,
+    pub side: TradeSide,
+    pub ticker: String,
+    pub trade_date: NaiveDate,
+    pub trade_id: Option<String>,
+    pub trade_money: Money,
+}
+
+impl StatementSectionWithTimezone for TradeConfirm {
+    fn from_node(
+        node: &impl AttributeSource,
+        tz_map: &HashMap<String, Tz>,
+    ) -> Result<TradeConfirm> {
+        let (side, is_cancel) = trade::parse_buy_sell(&node.get_attribute("buySell")?)?;
+
+        Ok(TradeConfirm {
+            account_id: node.get_attribute("accountId")?,
+            asset_category: AssetCategory::try_from(node.get_attribute("assetCategory")?.as_str())?,
+            codes: trade::parse_trade_codes(node),
+            commission: node.parse_attribute("commission")?,
+            commission_currency: Currency::try_from(
+                node.get_attribute("commissionCurrency")?.as_str(),
+            )?,
+            conid: node.parse_attribute("conid")?,
+            currency: Currency::try_from(node.get_attribute("currency")?.as_str())?,
+            execution_exchange: node.get_attribute("exchange")?,
+            execution_id: node.get_attribute("execID")?,
+            execution_timestamp_ms: time_utils::parse_ibkr_datetime_ms(
+                tz_map,
+                &node.get_attribute("dateTime")?,
+            )?,
+            ib_order_id: node.get_attribute("orderID")?,
+            is_api_order: trade::parse_is_api_order(node)?,
+            is_cancel,
+            listing_exchange: node.get_attribute("listingExchange")?,
+            net_cash: node.parse_attribute("netCash")?,
+            order_id: node.get_attribute("brokerageOrderID")?,
+            order_reference: node.get_attribute_opt("orderReference"),
+            order_type: OrderType::try_from(node.get_attribute("orderType")?.as_str())?,
+            price: node.parse_attribute("price")?,
+            proceeds: node.parse_attribute("proceeds")?,
+            quantity: node.parse_attribute("quantity")?,
+            settle_date: node.get_attribute_opt("settleDate"),
+            side,
+            ticker: node.get_attribute("symbol")?,
+            trade_date: NaiveDate::parse_from_str(&node.get_attribute("tradeDate")?, "%Y-%m-%d")?,
+            trade_id: node.get_attribute_opt("tradeID"),
+            trade_money: node.parse_attribute("amount")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use crate::error::SectionError;
+    use crate::flex_response::ResponseType;
+    use crate::money::money;
+    use anyhow::Result;
+
+    const TRADE_CONFIRM_EXAMPLE: &str = r##"
+        <FlexQueryResponse queryName="example-confirms" type="TF">
+            <FlexStatements count="1">
+                <FlexStatement accountId="U1234567" fromDate="2025-04-25" toDate="2025-04-25" period="Today" whenGenerated="2025-04-25;12:01:02 EDT">
+                    <TradeConfirms>
+                        <TradeConfirm accountId="U1234567" currency="USD" assetCategory="STK" symbol="ARGX" conid="276343981" listingExchange="NASDAQ" tradeID="7587063231" reportDate="2025-04-25" tradeDate="2025-04-25" settleDate="2025-04-28" dateTime="2025-04-25;10:19:55 EDT" transactionType="ExchTrade" exchange="BYX" quantity="1" price="606.57" amount="606.57" proceeds="-606.57" netCash="-607.570035" commission="-1.000035" commissionCurrency="USD" buySell="BUY" orderID="4015030800" execID="0000edae.680b59d1.01.01" brokerageOrderID="002ce642.00014b44.680b0ed6.0001" orderReference="" isAPIOrder="N" orderType="LMT" code="O" levelOfDetail="EXECUTION" />
+                        <TradeConfirm accountId="U1234567" currency="USD" assetCategory="STK" symbol="GEO" conid="158655765" listingExchange="NYSE" tradeID="7587946875" reportDate="2025-04-25" tradeDate="2025-04-25" settleDate="2025-04-28" dateTime="2025-04-25;11:24:28 EDT" transactionType="ExchTrade" exchange="NYSE" quantity="-1000" price="30.85" amount="-30850" proceeds="30850" netCash="30844.965" commission="-5.035" commissionCurrency="USD" buySell="SELL" orderID="4015577648" execID="00012e0e.680b7717.01.01" brokerageOrderID="002ce642.00014b44.680b0fbf.0001" orderReference="rebalance" isAPIOrder="Y" orderType="MKT" code="C" levelOfDetail="EXECUTION" />
+                    </TradeConfirms>
+                </FlexStatement>
+            </FlexStatements>
+        </FlexQueryResponse>
+        "##;
+
+    #[test]
+    fn trade_confirms_parse() -> Result<()> {
+        let response = Parser::new().parse_flex_response(TRADE_CONFIRM_EXAMPLE)?;
+        assert_eq!(
+            response.response_type,
+            Some(ResponseType::TradeConfirmation)
+        );
+        let statement = &response.statements[0];
+        // Confirmation reports have no AccountInformation row.
+        assert_eq!(statement.account_id(), "U1234567");
+        assert!(statement.trades.is_empty());
+        assert_eq!(statement.trade_confirms.len(), 2);
+
+        assert_eq!(
+            statement.trade_confirms[0],
+            TradeConfirm {
+                account_id: "U1234567".to_string(),
+                asset_category: AssetCategory::Stock,
+                codes: vec![TradeCode::Opening],
+                commission: money("-1.000035"),
+                commission_currency: Currency::USD,
+                conid: 276343981,
+                currency: Currency::USD,
+                execution_exchange: "BYX".to_string(),
+                execution_id: "0000edae.680b59d1.01.01".to_string(),
+                // 2025-04-25 10:19:55 EDT
+                execution_timestamp_ms: 1745590795000,
+                ib_order_id: "4015030800".to_string(),
+                is_api_order: false,
+                is_cancel: false,
+                listing_exchange: "NASDAQ".to_string(),
+                net_cash: money("-607.570035"),
+                order_id: "002ce642.00014b44.680b0ed6.0001".to_string(),
+                order_reference: None,
+                order_type: OrderType::Limit,
+                price: money("606.57"),
+                proceeds: money("-606.57"),
+                quantity: 1.0,
+                settle_date: Some("2025-04-28".to_string()),
+                side: TradeSide::Buy,
+                ticker: "ARGX".to_string(),
+                trade_date: NaiveDate::from_ymd_opt(2025, 4, 25).unwrap(),
+                trade_id: Some("7587063231".to_string()),
+                trade_money: money("606.57"),
+            }
+        );
+
+        let sell = &statement.trade_confirms[1];
+        assert_eq!(sell.side, TradeSide::Sell);
+        assert_eq!(sell.order_type, OrderType::Market);
+        assert!(sell.is_api_order);
+        assert_eq!(sell.order_reference.as_deref(), Some("rebalance"));
+        Ok(())
+    }
+
+    #[test]
+    fn trade_confirm_missing_attribute_is_an_error() {
+        let xml = TRADE_CONFIRM_EXAMPLE.replacen(r#" commissionCurrency="USD""#, "", 1);
+        let error = Parser::new().parse_flex_response(&xml).unwrap_err();
+        let section_error = error.downcast_ref::<SectionError>().unwrap();
+        assert_eq!(section_error.tag_name, "TradeConfirm");
+        assert_eq!(
+            section_error.error.to_string(),
+            "missing attribute commissionCurrency"
+        );
+    }
+}
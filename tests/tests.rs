@@ -9,7 +9,7 @@ use std::path::PathBuf;
 #[rstest]
 #[test]
 fn xml_files_parse_correctly(sample_statement_paths: Result<Vec<PathBuf>>) {
-    let parser = Parser::new().unwrap();
+    let parser = Parser::new();
 
     for path in sample_statement_paths.unwrap() {
         let xml_content = std::fs::read_to_string(path).expect("Failed to read XML file");
@@ -18,3 +18,15 @@ fn xml_files_parse_correctly(sample_statement_paths: Result<Vec<PathBuf>>) {
             .expect("Failed to parse XML content");
     }
 }
+
+#[rstest]
+#[test]
+fn xml_files_parse_in_batch(sample_statement_paths: Result<Vec<PathBuf>>) {
+    let paths = sample_statement_paths.unwrap();
+    let results = Parser::new().parse_paths(&paths);
+
+    assert_eq!(results.len(), paths.len());
+    for (path, result) in paths.iter().zip(results) {
+        result.unwrap_or_else(|e| panic!("Failed to parse {}: {}", path.display(), e));
+    }
+}